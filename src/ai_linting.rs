@@ -2,8 +2,9 @@
 use async_openai::{Client, types::{CreateChatCompletionRequestArgs, ChatCompletionRequestMessage}};
 #[cfg(feature = "ai")]
 use serde_json::json;
-use crate::types::RustFileSnapshot;
+use crate::types::{RustFileSnapshot, doc_summary};
 
+#[cfg_attr(not(feature = "ai"), allow(dead_code))]
 pub struct AILinter {
     provider: AIProvider,
     max_tokens: usize,
@@ -22,6 +23,7 @@ pub struct AIAnalysis {
     pub quality_score: Option<f32>,
 }
 
+#[cfg_attr(not(feature = "ai"), allow(dead_code))]
 impl AILinter {
     pub fn new(provider: AIProvider) -> Self {
         Self {
@@ -73,8 +75,8 @@ impl AILinter {
         let total_structs: usize = snapshots.iter().map(|s| s.structs.len()).sum();
         let total_enums: usize = snapshots.iter().map(|s| s.enums.len()).sum();
         
-        context.push_str(&format!("# Rust Project Analysis Request\n\n"));
-        context.push_str(&format!("## Project Overview\n"));
+        context.push_str("# Rust Project Analysis Request\n\n");
+        context.push_str("## Project Overview\n");
         context.push_str(&format!("- Files: {}\n", snapshots.len()));
         context.push_str(&format!("- Functions: {}\n", total_functions));
         context.push_str(&format!("- Structs: {}\n", total_structs));
@@ -84,15 +86,18 @@ impl AILinter {
         // Include all code details in one batch
         for snapshot in snapshots {
             context.push_str(&format!("### File: {}\n\n", snapshot.path));
+            if let Some(summary) = doc_summary(snapshot.docs.as_deref()) {
+                context.push_str(&format!("{}\n\n", summary));
+            }
             
             if !snapshot.functions.is_empty() {
                 context.push_str("**Functions:**\n");
                 for func in &snapshot.functions {
                     context.push_str(&format!(
-                        "- `{}({})` - {} variables\n",
-                        func.name,
-                        func.args.join(", "),
-                        func.variables.len()
+                        "- `{}` - {} variables{}\n",
+                        func.signature_string(),
+                        func.variables.len(),
+                        doc_suffix(func.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -102,10 +107,11 @@ impl AILinter {
                 context.push_str("**Structs:**\n");
                 for strct in &snapshot.structs {
                     context.push_str(&format!(
-                        "- `{}` - {} fields, {} methods\n",
+                        "- `{}` - {} fields, {} methods{}\n",
                         strct.name,
                         strct.fields.len(),
-                        strct.methods.len()
+                        strct.methods.len(),
+                        doc_suffix(strct.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -115,9 +121,10 @@ impl AILinter {
                 context.push_str("**Enums:**\n");
                 for enm in &snapshot.enums {
                     context.push_str(&format!(
-                        "- `{}` - {} variants\n",
+                        "- `{}` - {} variants{}\n",
                         enm.name,
-                        enm.variants.len()
+                        enm.variants.len(),
+                        doc_suffix(enm.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -280,6 +287,9 @@ impl AILinter {
         
         for snapshot in snapshots {
             context.push_str(&format!("### File: {}\n\n", snapshot.path));
+            if let Some(summary) = doc_summary(snapshot.docs.as_deref()) {
+                context.push_str(&format!("The author describes this file as: {}\n\n", summary));
+            }
             context.push_str("**What this file contains:**\n\n");
             
             // Explain functions
@@ -287,10 +297,11 @@ impl AILinter {
                 context.push_str(&format!("This file has {} functions (tasks the program can do):\n\n", snapshot.functions.len()));
                 for func in &snapshot.functions {
                     context.push_str(&format!(
-                        "- `{}`: Takes {} input{}, processes data\n",
+                        "- `{}`: Takes {} input{}, processes data{}\n",
                         func.name,
                         func.args.len(),
-                        if func.args.len() == 1 { "" } else { "s" }
+                        if func.args.len() == 1 { "" } else { "s" },
+                        doc_suffix(func.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -304,10 +315,11 @@ impl AILinter {
                 ));
                 for strct in &snapshot.structs {
                     context.push_str(&format!(
-                        "- `{}`: A container with {} piece{} of information\n",
+                        "- `{}`: A container with {} piece{} of information{}\n",
                         strct.name,
                         strct.fields.len(),
-                        if strct.fields.len() == 1 { "" } else { "s" }
+                        if strct.fields.len() == 1 { "" } else { "s" },
+                        doc_suffix(strct.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -317,9 +329,10 @@ impl AILinter {
             if !snapshot.enums.is_empty() {
                 for enm in &snapshot.enums {
                     context.push_str(&format!(
-                        "- `{}`: Represents {} different possible states or types\n",
+                        "- `{}`: Represents {} different possible states or types{}\n",
                         enm.name,
-                        enm.variants.len()
+                        enm.variants.len(),
+                        doc_suffix(enm.docs.as_deref())
                     ));
                 }
                 context.push('\n');
@@ -429,3 +442,10 @@ impl AILinter {
             .to_string())
     }
 }
+
+/// ` - "summary"` for documented items, so the model sees the author's own description.
+fn doc_suffix(docs: Option<&str>) -> String {
+    doc_summary(docs)
+        .map(|summary| format!(" - \"{}\"", summary))
+        .unwrap_or_default()
+}
@@ -0,0 +1,193 @@
+use crate::cfg::CfgContext;
+use crate::types::RustFileSnapshot;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Bump whenever a snapshot type or `CachedFile` changes shape, so caches written by
+/// an older layout are discarded instead of misread.
+const CACHE_SCHEMA: u32 = 4;
+const CACHE_FILE: &str = "snapshots.bin";
+/// Entries not used by the last `MAX_AGE` scans are dropped, and at most `MAX_ENTRIES`
+/// are kept, the most recently used first.
+const MAX_AGE: u64 = 16;
+const MAX_ENTRIES: usize = 20_000;
+
+/// What the engine keeps of a parsed file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    pub snapshot: RustFileSnapshot, // Already cfg-stripped
+    pub module_items: String, // The file's inner attributes and `mod` items, unstripped, as source
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheContents<E> {
+    version: String,
+    schema: u32,
+    generation: u64, // Number of saves so far
+    entries: E,      // Key -> (generation of the last scan using it, entry)
+}
+
+/// On-disk cache of parsed files, keyed by content hash, path and `cfg` selection.
+///
+/// Entries are loaded once per scan. `save` writes back those the scan asked for along
+/// with the stored ones, so runs with other features or members keep theirs; entries
+/// of deleted or changed files age out.
+pub struct SnapshotCache {
+    dir: PathBuf,
+    generation: u64,
+    stored: HashMap<String, (u64, CachedFile)>,
+    used: Mutex<HashMap<String, CachedFile>>,
+}
+
+impl SnapshotCache {
+    /// Opens the cache in `dir`. A missing, corrupt or outdated cache starts out empty.
+    pub fn open(dir: PathBuf) -> Self {
+        let (generation, stored) = fs::read(dir.join(CACHE_FILE)).ok()
+            .and_then(|bytes| bincode::deserialize::<CacheContents<HashMap<String, (u64, CachedFile)>>>(&bytes).ok())
+            .filter(|contents| contents.version == env!("CARGO_PKG_VERSION") && contents.schema == CACHE_SCHEMA)
+            .map(|contents| (contents.generation, contents.entries))
+            .unwrap_or_default();
+        Self { dir, generation, stored, used: Mutex::new(HashMap::new()) }
+    }
+
+    /// Key for `path` with content hash `hash`, parsed under `cfg`.
+    pub fn key(hash: &str, path: &Path, cfg: &CfgContext) -> String {
+        let mut features: Vec<&String> = cfg.features.iter().collect();
+        features.sort();
+
+        let mut hasher = Sha256::new();
+        hasher.update(hash);
+        hasher.update(path.to_string_lossy().as_bytes());
+        for feature in features {
+            hasher.update(b"\0");
+            hasher.update(feature);
+        }
+        hasher.update(b"\0");
+        hasher.update(cfg.target_os.as_deref().unwrap_or("*"));
+        hex::encode(hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedFile> {
+        let mut used = self.used.lock().unwrap();
+        if let Some(entry) = used.get(key) {
+            return Some(entry.clone());
+        }
+        let entry = self.stored.get(key)?.1.clone();
+        used.insert(key.to_string(), entry.clone());
+        Some(entry)
+    }
+
+    pub fn insert(&self, key: String, entry: CachedFile) {
+        self.used.lock().unwrap().insert(key, entry);
+    }
+
+    /// Writes the entries used since `open`, then the stored ones that are neither too
+    /// old nor past the size limit, replacing the previous cache.
+    pub fn save(&self) -> std::io::Result<()> {
+        let used = self.used.lock().unwrap();
+        let generation = self.generation + 1;
+        let mut entries: HashMap<&str, (u64, &CachedFile)> = used.iter().map(|(key, entry)| (key.as_str(), (generation, entry))).collect();
+
+        let mut others: Vec<(&String, &(u64, CachedFile))> = self.stored.iter()
+            .filter(|(key, (last_used, _))| !used.contains_key(*key) && generation - last_used <= MAX_AGE)
+            .collect();
+        others.sort_by_key(|(key, (last_used, _))| (Reverse(*last_used), *key));
+        let room = MAX_ENTRIES.saturating_sub(entries.len());
+        entries.extend(others.into_iter().take(room).map(|(key, (last_used, entry))| (key.as_str(), (*last_used, entry))));
+
+        let contents = CacheContents {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            schema: CACHE_SCHEMA,
+            generation,
+            entries,
+        };
+        let bytes = bincode::serialize(&contents).map_err(std::io::Error::other)?;
+        fs::create_dir_all(&self.dir)?;
+        // Write-then-rename so an interrupted save never leaves a truncated cache behind
+        let tmp = self.dir.join(format!("{}.tmp", CACHE_FILE));
+        fs::write(&tmp, bytes)?;
+        fs::rename(tmp, self.dir.join(CACHE_FILE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FunctionSnapshot;
+
+    #[test]
+    fn test_cache_round_trip_and_invalidation() {
+        let dir = std::env::temp_dir().join(format!("furnace-cache-test-{}", std::process::id()));
+        let cfg = CfgContext::default();
+        let key = SnapshotCache::key("abc", Path::new("src/lib.rs"), &cfg);
+        let entry = CachedFile {
+            snapshot: RustFileSnapshot {
+                path: "src/lib.rs".to_string(),
+                functions: vec![FunctionSnapshot { name: "run".to_string(), ..FunctionSnapshot::default() }],
+                ..RustFileSnapshot::default()
+            },
+            module_items: "mod a ;".to_string(),
+        };
+
+        let cache = SnapshotCache::open(dir.clone());
+        assert!(cache.get(&key).is_none());
+        cache.insert(key.clone(), entry);
+        cache.save().unwrap();
+
+        let cache = SnapshotCache::open(dir.clone());
+        let cached = cache.get(&key).unwrap();
+        assert_eq!(cached.snapshot.functions[0].name, "run");
+        assert_eq!(cached.module_items, "mod a ;");
+
+        // A different feature set is a different key
+        let featured = CfgContext { features: ["extra".to_string()].into(), ..CfgContext::default() };
+        assert_ne!(SnapshotCache::key("abc", Path::new("src/lib.rs"), &featured), key);
+
+        // Caches from another schema are dropped
+        let stale = CacheContents::<HashMap<String, (u64, CachedFile)>> { version: env!("CARGO_PKG_VERSION").to_string(), schema: CACHE_SCHEMA + 1, generation: 0, entries: HashMap::new() };
+        fs::write(dir.join(CACHE_FILE), bincode::serialize(&stale).unwrap()).unwrap();
+        assert!(SnapshotCache::open(dir.clone()).get(&key).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_keeps_entries_of_other_runs() {
+        let dir = std::env::temp_dir().join(format!("furnace-cache-merge-test-{}", std::process::id()));
+        let entry = |name: &str| CachedFile {
+            snapshot: RustFileSnapshot { path: name.to_string(), ..RustFileSnapshot::default() },
+            module_items: String::new(),
+        };
+
+        // A run with other features uses other keys; both survive its save
+        let cache = SnapshotCache::open(dir.clone());
+        cache.insert("default".to_string(), entry("a.rs"));
+        cache.save().unwrap();
+        let cache = SnapshotCache::open(dir.clone());
+        cache.insert("featured".to_string(), entry("a.rs"));
+        cache.save().unwrap();
+        let cache = SnapshotCache::open(dir.clone());
+        assert!(cache.get("default").is_some());
+        assert!(cache.get("featured").is_some());
+        cache.save().unwrap();
+
+        // Entries no scan asks for age out
+        for _ in 0..MAX_AGE {
+            SnapshotCache::open(dir.clone()).save().unwrap();
+        }
+        let cache = SnapshotCache::open(dir.clone());
+        assert!(cache.get("default").is_some());
+        cache.save().unwrap();
+        for _ in 0..=MAX_AGE {
+            SnapshotCache::open(dir.clone()).save().unwrap();
+        }
+        assert!(SnapshotCache::open(dir.clone()).get("default").is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use crate::graph::{location_key, NodeIndex, ProjectGraph};
+use crate::symbols::{parent_of, Resolution, Symbol, SymbolKind, SymbolTable};
+use crate::types::{CallSnapshot, FunctionSnapshot, Location, Span, StructSnapshot};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A function of one target: a free function, or a method under its type or trait.
+#[derive(Debug, Clone, Serialize)]
+pub struct CallNode {
+    pub crate_idx: usize,
+    pub target_idx: usize,
+    pub path: String, // Symbol path: `crate::engine::TraversalEngine::scan`
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Callee {
+    Function(usize),  // Index into `CallGraph::nodes`
+    External(String), // Path into another crate, or the callee as written when it could not be resolved
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallEdge {
+    pub caller: usize, // Index into `CallGraph::nodes`
+    pub callee: Callee,
+    pub is_method: bool,
+    pub location: Location, // The call expression
+}
+
+/// Calls between the functions of a `ProjectGraph`, resolved through the symbol
+/// tables of its targets.
+///
+/// Path calls resolve like `use` paths; method calls resolve when the receiver is
+/// `self`, a `self.field`, or an argument or variable whose type is written out.
+/// Calls to other crates of the graph are followed into them. Anything else is an
+/// external callee. Tuple struct and variant constructors are not calls.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CallGraph {
+    pub nodes: Vec<CallNode>, // By crate, then target, then path
+    pub edges: Vec<CallEdge>, // By caller, then source order
+    #[serde(skip)]
+    index: NodeIndex,
+}
+
+impl CallGraph {
+    /// Builds the call graph of `graph`, whose symbol tables must already be built.
+    pub fn build(graph: &ProjectGraph) -> Self {
+        let mut functions: HashMap<(&str, usize, usize), &FunctionSnapshot> = HashMap::new();
+        let mut structs: HashMap<(&str, usize, usize), &StructSnapshot> = HashMap::new();
+        for snapshot in graph.reached_snapshots() {
+            let extern_functions = snapshot.extern_blocks.iter().flat_map(|block| &block.functions);
+            for func in snapshot.all_functions().chain(extern_functions) {
+                functions.insert(location_key(&snapshot.path, &func.span), func);
+            }
+            for strct in &snapshot.structs {
+                structs.insert(location_key(&snapshot.path, &strct.span), strct);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut callers = Vec::new();
+        for (crate_idx, crate_node) in graph.crates.iter().enumerate() {
+            for (target_idx, target) in crate_node.targets.iter().enumerate() {
+                let symbols = target.symbols.symbols().filter(|symbol| matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method));
+                for symbol in symbols {
+                    let Some(func) = functions.get(&location_key(&symbol.location.path, &symbol.location.span)) else { continue };
+                    callers.push((nodes.len(), *func, symbol, &target.symbols));
+                    nodes.push(CallNode { crate_idx, target_idx, path: symbol.path.clone(), location: symbol.location.clone() });
+                }
+            }
+        }
+
+        let mut index = NodeIndex::new(graph.crates.len(), nodes.iter().map(|node| (node.crate_idx, node.target_idx, node.path.as_str())));
+        let resolver = Resolver { graph, structs, index: &index };
+
+        let mut edges = Vec::new();
+        for (caller, func, symbol, table) in callers {
+            let node = &nodes[caller];
+            for call in &func.calls {
+                let Some(callee) = resolver.callee(node, symbol, table, func, call) else { continue };
+                let location = Location { path: symbol.location.path.clone(), span: call.span };
+                edges.push(CallEdge { caller, callee, is_method: call.is_method, location });
+            }
+        }
+        index.link(edges.iter().map(|edge| match edge.callee {
+            Callee::Function(callee) => (edge.caller, Some(callee)),
+            Callee::External(_) => (edge.caller, None),
+        }));
+        CallGraph { nodes, edges, index }
+    }
+
+    /// Node of function `path` of crate `crate_idx`, in its library target if it has one.
+    pub fn node(&self, crate_idx: usize, path: &str) -> Option<usize> {
+        self.index.node(crate_idx, path)
+    }
+
+    /// Calls made by `node`, in source order.
+    pub fn callees(&self, node: usize) -> impl Iterator<Item = &CallEdge> {
+        self.index.outgoing(node).iter().map(|&edge| &self.edges[edge])
+    }
+
+    /// Calls made to `node`.
+    pub fn callers(&self, node: usize) -> impl Iterator<Item = &CallEdge> {
+        self.index.incoming(node).iter().map(|&edge| &self.edges[edge])
+    }
+
+    /// Every function that reaches `node` through a chain of calls, nearest first:
+    /// what a change to `node` may affect. Includes `node` only if it is recursive.
+    pub fn transitive_callers(&self, node: usize) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([node]);
+        while let Some(current) = queue.pop_front() {
+            for edge in self.callers(current) {
+                if seen.insert(edge.caller) {
+                    order.push(edge.caller);
+                    queue.push_back(edge.caller);
+                }
+            }
+        }
+        order
+    }
+}
+
+struct Resolver<'g> {
+    graph: &'g ProjectGraph,
+    structs: HashMap<(&'g str, usize, usize), &'g StructSnapshot>,
+    index: &'g NodeIndex,
+}
+
+impl Resolver<'_> {
+    /// What `call`, made by `func` (the function of `node` and `symbol`), calls. None for
+    /// constructors and calls through local closures or function pointers.
+    fn callee(&self, node: &CallNode, symbol: &Symbol, table: &SymbolTable, func: &FunctionSnapshot, call: &CallSnapshot) -> Option<Callee> {
+        let self_ty = parent_of(&symbol.path).filter(|owner| symbol.kind == SymbolKind::Method && table.is_type(owner));
+        let target = if call.is_method {
+            self.receiver_type(symbol, table, func, call, self_ty).map(|ty| format!("{}::{}", ty, call.callee))
+        } else {
+            // Only tuple structs and enum variants are capitalized, by convention
+            let name = call.callee.rsplit("::").next().unwrap_or_default();
+            if name.starts_with(char::is_uppercase) {
+                return None;
+            }
+            let is_local = func.args.iter().chain(func.variables.iter().map(|var| &var.name)).any(|local| *local == call.callee);
+            if is_local {
+                return None;
+            }
+            let path = match (call.callee.strip_prefix("Self::"), self_ty) {
+                (Some(rest), Some(owner)) => format!("{}::{}", owner, rest),
+                _ => call.callee.clone(),
+            };
+            match table.resolve(&symbol.module, &path) {
+                Some(Resolution::Local(local)) => Some(local),
+                Some(Resolution::External(external)) => return Some(self.external(node.crate_idx, external)),
+                None => None,
+            }
+        };
+        let found = target.and_then(|path| self.index.get(node.crate_idx, node.target_idx, &path));
+        Some(found.map_or_else(|| Callee::External(call.callee.clone()), Callee::Function))
+    }
+
+    /// A path into another crate: a function there if the crate is in the graph.
+    fn external(&self, crate_idx: usize, path: String) -> Callee {
+        self.graph.find_symbol(crate_idx, &path)
+            .and_then(|(dep, symbol)| self.index.node(dep, &symbol.path))
+            .map_or(Callee::External(path), Callee::Function)
+    }
+
+    /// Fully-qualified type of a method call's receiver, when it is written out.
+    fn receiver_type(&self, symbol: &Symbol, table: &SymbolTable, func: &FunctionSnapshot, call: &CallSnapshot, self_ty: Option<&str>) -> Option<String> {
+        let receiver = call.receiver.as_deref()?;
+        if receiver == "self" {
+            return self_ty.map(String::from);
+        }
+        if let Some(field) = receiver.strip_prefix("self.") {
+            // Field types are written in the struct's own module
+            let owner = table.get(self_ty?)?;
+            let strct = self.structs.get(&location_key(&owner.location.path, &owner.location.span))?;
+            let path = strct.fields.iter().find(|f| f.name == field)?.type_path.as_deref()?;
+            return resolve_type(table, &owner.module, path, self_ty);
+        }
+
+        // The latest binding of the name before the call, else an argument
+        let before = |span: &Span| (span.start_line, span.start_column) < (call.span.start_line, call.span.start_column);
+        let path = match func.variables.iter().rev().find(|var| var.name == receiver && before(&var.span)) {
+            Some(var) => var.type_path.as_deref()?,
+            None => func.signature.inputs.iter().find(|arg| arg.pattern.trim_start_matches("mut ") == receiver)?.type_path.as_deref()?,
+        };
+        resolve_type(table, &symbol.module, path, self_ty)
+    }
+}
+
+/// `path`, the path of a receiver's type, as a type of `table`.
+fn resolve_type(table: &SymbolTable, module: &str, path: &str, self_ty: Option<&str>) -> Option<String> {
+    if path == "Self" {
+        return self_ty.map(String::from);
+    }
+    match table.resolve(module, path)? {
+        Resolution::Local(local) if table.is_type(&local) => Some(local),
+        _ => None,
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, HashSet};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Attribute, Meta, Token};
+
+/// Features requested for a scan, mirroring cargo's `--features`, `--all-features`
+/// and `--no-default-features`.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSelection {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+}
+
+/// The configuration `#[cfg(..)]` predicates are evaluated against.
+///
+/// `test` and `debug_assertions` are always on, so test modules stay visible, the
+/// way an IDE sees a crate. Target predicates (`unix`, `target_os = ".."`) are only
+/// decided when a target OS is given; otherwise both sides of the cfg are kept.
+/// Any other unset name, such as `docsrs` or `loom`, is off.
+#[derive(Debug, Clone, Default)]
+pub struct CfgContext {
+    pub features: HashSet<String>,
+    pub target_os: Option<String>,
+}
+
+impl CfgContext {
+    /// Enabled features of a crate, following the `[features]` table transitively.
+    /// Optional dependencies never named as `dep:x` count as implicit features.
+    pub fn resolve(
+        table: &BTreeMap<String, Vec<String>>,
+        optional_deps: &[String],
+        selection: &FeatureSelection,
+        target_os: Option<String>,
+    ) -> Self {
+        let explicit_deps: HashSet<&str> = table.values()
+            .flatten()
+            .filter_map(|entry| entry.strip_prefix("dep:"))
+            .collect();
+        let implicit: Vec<&String> = optional_deps.iter()
+            .filter(|dep| !explicit_deps.contains(dep.as_str()))
+            .collect();
+
+        let mut pending: Vec<String> = if selection.all_features {
+            table.keys().chain(implicit.iter().copied()).cloned().collect()
+        } else {
+            let mut requested = selection.features.clone();
+            if !selection.no_default_features {
+                requested.push("default".to_string());
+            }
+            requested
+        };
+
+        let mut features = HashSet::new();
+        while let Some(feature) = pending.pop() {
+            if !features.insert(feature.clone()) {
+                continue;
+            }
+            for entry in table.get(&feature).into_iter().flatten() {
+                if entry.starts_with("dep:") {
+                    continue;
+                }
+                // `dep/feature` turns on the dependency's implicit feature; `dep?/feature` does not
+                match entry.split_once('/') {
+                    Some((dep, _)) if !dep.ends_with('?') && implicit.iter().any(|name| *name == dep) => {
+                        pending.push(dep.to_string());
+                    }
+                    Some(_) => {}
+                    None => pending.push(entry.clone()),
+                }
+            }
+        }
+
+        CfgContext { features, target_os }
+    }
+
+    /// `Some(true)`/`Some(false)` when the predicate is decided, `None` when it depends
+    /// on something this context leaves open (the target, or malformed input).
+    pub fn eval(&self, predicate: &Meta) -> Option<bool> {
+        match predicate {
+            Meta::Path(path) => {
+                let name = path.get_ident()?.to_string();
+                match name.as_str() {
+                    "test" | "debug_assertions" => Some(true),
+                    "unix" | "windows" => self.target_family().map(|family| family == name),
+                    _ => Some(false),
+                }
+            }
+            Meta::NameValue(name_value) => {
+                let key = name_value.path.get_ident()?.to_string();
+                let value = match &name_value.value {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(value), .. }) => value.value(),
+                    _ => return None,
+                };
+                match key.as_str() {
+                    "feature" => Some(self.features.contains(&value)),
+                    "target_os" => self.target_os.as_ref().map(|os| *os == value),
+                    "target_family" => self.target_family().map(|family| family == value),
+                    key if key.starts_with("target_") || key == "panic" => None,
+                    _ => Some(false),
+                }
+            }
+            Meta::List(list) => {
+                let name = list.path.get_ident()?.to_string();
+                let args = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok()?;
+                let values: Vec<Option<bool>> = args.iter().map(|arg| self.eval(arg)).collect();
+                match name.as_str() {
+                    "all" if values.contains(&Some(false)) => Some(false),
+                    "all" if values.contains(&None) => None,
+                    "all" => Some(true),
+                    "any" if values.contains(&Some(true)) => Some(true),
+                    "any" if values.contains(&None) => None,
+                    "any" => Some(false),
+                    "not" if values.len() == 1 => values[0].map(|value| !value),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// False only when some `#[cfg(..)]` among `attrs` is decided false.
+    pub fn is_active(&self, attrs: &[Attribute]) -> bool {
+        attrs.iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .all(|attr| attr.parse_args::<Meta>().ok().and_then(|predicate| self.eval(&predicate)) != Some(false))
+    }
+
+    /// Replaces each `#[cfg_attr(pred, a, b)]` with `#[a] #[b]` when `pred` holds and
+    /// removes it when it does not. Undecided ones are left in place.
+    pub fn expand_cfg_attr(&self, attrs: &mut Vec<Attribute>) {
+        let mut expanded = Vec::with_capacity(attrs.len());
+        let mut pending: Vec<Attribute> = std::mem::take(attrs);
+        pending.reverse();
+        while let Some(attr) = pending.pop() {
+            if !attr.path().is_ident("cfg_attr") {
+                expanded.push(attr);
+                continue;
+            }
+            let Ok(args) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+                expanded.push(attr);
+                continue;
+            };
+            let mut args = args.into_iter();
+            let Some(predicate) = args.next() else { continue };
+            match self.eval(&predicate) {
+                // Expanded attributes may be `cfg_attr`s themselves, so they go back on the stack
+                Some(true) => pending.extend(args.rev().map(|meta| Attribute { meta, ..attr.clone() })),
+                Some(false) => {}
+                None => expanded.push(attr),
+            }
+        }
+        *attrs = expanded;
+    }
+
+    /// Expands `cfg_attr`s in place and tells whether the owner of `attrs` survives.
+    fn keep(&self, attrs: &mut Vec<Attribute>) -> bool {
+        self.expand_cfg_attr(attrs);
+        self.is_active(attrs)
+    }
+
+    /// Drops inactive items, impl/trait/extern members, fields, variants, statements and
+    /// match arms from `file`, and expands `cfg_attr` on everything that stays.
+    pub fn strip_file(&self, file: &mut syn::File) {
+        CfgStripper { cfg: self }.visit_file_mut(file);
+    }
+
+    fn target_family(&self) -> Option<&'static str> {
+        self.target_os.as_deref().map(|os| if os == "windows" { "windows" } else { "unix" })
+    }
+}
+
+struct CfgStripper<'a> {
+    cfg: &'a CfgContext,
+}
+
+impl CfgStripper<'_> {
+    fn retain<T>(&self, items: &mut Vec<T>, attrs_of: fn(&mut T) -> Option<&mut Vec<Attribute>>) {
+        items.retain_mut(|item| attrs_of(item).is_none_or(|attrs| self.cfg.keep(attrs)));
+    }
+
+    fn retain_punctuated<T, P: Default>(&self, items: &mut Punctuated<T, P>, attrs_of: fn(&mut T) -> &mut Vec<Attribute>) {
+        *items = std::mem::take(items)
+            .into_iter()
+            .filter_map(|mut item| self.cfg.keep(attrs_of(&mut item)).then_some(item))
+            .collect();
+    }
+}
+
+impl VisitMut for CfgStripper<'_> {
+    fn visit_file_mut(&mut self, file: &mut syn::File) {
+        if !self.cfg.keep(&mut file.attrs) {
+            file.items.clear();
+        }
+        self.retain(&mut file.items, item_attrs);
+        visit_mut::visit_file_mut(self, file);
+    }
+
+    fn visit_item_mod_mut(&mut self, node: &mut syn::ItemMod) {
+        if let Some((_, items)) = &mut node.content {
+            self.retain(items, item_attrs);
+        }
+        visit_mut::visit_item_mod_mut(self, node);
+    }
+
+    fn visit_item_impl_mut(&mut self, node: &mut syn::ItemImpl) {
+        self.retain(&mut node.items, |item| match item {
+            syn::ImplItem::Const(item) => Some(&mut item.attrs),
+            syn::ImplItem::Fn(item) => Some(&mut item.attrs),
+            syn::ImplItem::Type(item) => Some(&mut item.attrs),
+            syn::ImplItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        });
+        visit_mut::visit_item_impl_mut(self, node);
+    }
+
+    fn visit_item_trait_mut(&mut self, node: &mut syn::ItemTrait) {
+        self.retain(&mut node.items, |item| match item {
+            syn::TraitItem::Const(item) => Some(&mut item.attrs),
+            syn::TraitItem::Fn(item) => Some(&mut item.attrs),
+            syn::TraitItem::Type(item) => Some(&mut item.attrs),
+            syn::TraitItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        });
+        visit_mut::visit_item_trait_mut(self, node);
+    }
+
+    fn visit_item_foreign_mod_mut(&mut self, node: &mut syn::ItemForeignMod) {
+        self.retain(&mut node.items, |item| match item {
+            syn::ForeignItem::Fn(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Static(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Type(item) => Some(&mut item.attrs),
+            syn::ForeignItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        });
+        visit_mut::visit_item_foreign_mod_mut(self, node);
+    }
+
+    fn visit_item_enum_mut(&mut self, node: &mut syn::ItemEnum) {
+        self.retain_punctuated(&mut node.variants, |variant| &mut variant.attrs);
+        visit_mut::visit_item_enum_mut(self, node);
+    }
+
+    fn visit_fields_named_mut(&mut self, node: &mut syn::FieldsNamed) {
+        self.retain_punctuated(&mut node.named, |field| &mut field.attrs);
+        visit_mut::visit_fields_named_mut(self, node);
+    }
+
+    fn visit_fields_unnamed_mut(&mut self, node: &mut syn::FieldsUnnamed) {
+        self.retain_punctuated(&mut node.unnamed, |field| &mut field.attrs);
+        visit_mut::visit_fields_unnamed_mut(self, node);
+    }
+
+    fn visit_block_mut(&mut self, node: &mut syn::Block) {
+        self.retain(&mut node.stmts, |stmt| match stmt {
+            syn::Stmt::Local(local) => Some(&mut local.attrs),
+            syn::Stmt::Item(item) => item_attrs(item),
+            _ => None,
+        });
+        visit_mut::visit_block_mut(self, node);
+    }
+
+    fn visit_expr_match_mut(&mut self, node: &mut syn::ExprMatch) {
+        self.retain(&mut node.arms, |arm| Some(&mut arm.attrs));
+        visit_mut::visit_expr_match_mut(self, node);
+    }
+}
+
+fn item_attrs(item: &mut syn::Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        syn::Item::Const(item) => Some(&mut item.attrs),
+        syn::Item::Enum(item) => Some(&mut item.attrs),
+        syn::Item::ExternCrate(item) => Some(&mut item.attrs),
+        syn::Item::Fn(item) => Some(&mut item.attrs),
+        syn::Item::ForeignMod(item) => Some(&mut item.attrs),
+        syn::Item::Impl(item) => Some(&mut item.attrs),
+        syn::Item::Macro(item) => Some(&mut item.attrs),
+        syn::Item::Mod(item) => Some(&mut item.attrs),
+        syn::Item::Static(item) => Some(&mut item.attrs),
+        syn::Item::Struct(item) => Some(&mut item.attrs),
+        syn::Item::Trait(item) => Some(&mut item.attrs),
+        syn::Item::TraitAlias(item) => Some(&mut item.attrs),
+        syn::Item::Type(item) => Some(&mut item.attrs),
+        syn::Item::Union(item) => Some(&mut item.attrs),
+        syn::Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn context(features: &[&str], target_os: Option<&str>) -> CfgContext {
+        CfgContext {
+            features: features.iter().map(|f| f.to_string()).collect(),
+            target_os: target_os.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_predicate_evaluation() {
+        let cfg = context(&["ai"], None);
+        assert_eq!(cfg.eval(&parse_quote!(feature = "ai")), Some(true));
+        assert_eq!(cfg.eval(&parse_quote!(not(feature = "ai"))), Some(false));
+        assert_eq!(cfg.eval(&parse_quote!(all(test, feature = "ai"))), Some(true));
+        assert_eq!(cfg.eval(&parse_quote!(any(docsrs, feature = "other"))), Some(false));
+        assert_eq!(cfg.eval(&parse_quote!(target_os = "linux")), None);
+        assert_eq!(cfg.eval(&parse_quote!(any(unix, feature = "ai"))), Some(true));
+        assert_eq!(cfg.eval(&parse_quote!(all(unix, feature = "ai"))), None);
+
+        let linux = context(&[], Some("linux"));
+        assert_eq!(linux.eval(&parse_quote!(unix)), Some(true));
+        assert_eq!(linux.eval(&parse_quote!(windows)), Some(false));
+        assert_eq!(linux.eval(&parse_quote!(target_os = "macos")), Some(false));
+    }
+
+    #[test]
+    fn test_feature_resolution() {
+        let table: BTreeMap<String, Vec<String>> = [
+            ("default".to_string(), vec!["std".to_string()]),
+            ("std".to_string(), vec![]),
+            ("ai".to_string(), vec!["tokio".to_string(), "serde/derive".to_string()]),
+            ("net".to_string(), vec!["dep:reqwest".to_string()]),
+        ].into_iter().collect();
+        let optional = vec!["tokio".to_string(), "reqwest".to_string(), "serde".to_string()];
+
+        let defaults = CfgContext::resolve(&table, &optional, &FeatureSelection::default(), None);
+        assert!(defaults.features.contains("std"));
+        assert!(!defaults.features.contains("ai"));
+
+        let selection = FeatureSelection { features: vec!["ai".to_string()], no_default_features: true, ..FeatureSelection::default() };
+        let ai = CfgContext::resolve(&table, &optional, &selection, None);
+        assert!(ai.features.contains("tokio") && ai.features.contains("serde"));
+        assert!(!ai.features.contains("std"));
+
+        let all = CfgContext::resolve(&table, &optional, &FeatureSelection { all_features: true, ..FeatureSelection::default() }, None);
+        assert!(all.features.contains("net") && all.features.contains("tokio"));
+        // `dep:reqwest` hides the implicit feature
+        assert!(!all.features.contains("reqwest"));
+    }
+
+    #[test]
+    fn test_strip_file() {
+        let mut file: syn::File = parse_quote! {
+            #[cfg(feature = "ai")]
+            fn analyze() {}
+            #[cfg(not(feature = "ai"))]
+            fn analyze() {}
+
+            #[cfg_attr(feature = "ai", derive(Debug))]
+            #[cfg_attr(not(feature = "ai"), derive(Clone))]
+            struct Report {
+                #[cfg(feature = "ai")]
+                score: u8,
+                name: String,
+            }
+
+            impl Report {
+                #[cfg(docsrs)]
+                fn docs_only(&self) {}
+            }
+        };
+        context(&[], None).strip_file(&mut file);
+
+        assert_eq!(file.items.len(), 3);
+        let syn::Item::Fn(analyze) = &file.items[0] else { panic!("expected fn") };
+        assert!(analyze.attrs[0].meta.path().is_ident("cfg"));
+        let syn::Item::Struct(report) = &file.items[1] else { panic!("expected struct") };
+        assert_eq!(report.attrs.len(), 1);
+        assert_eq!(crate::visitor::tokens_to_string(&report.attrs[0].meta), "derive(Clone)");
+        assert_eq!(report.fields.len(), 1);
+        let syn::Item::Impl(imp) = &file.items[2] else { panic!("expected impl") };
+        assert!(imp.items.is_empty());
+    }
+}
@@ -2,21 +2,12 @@ use serde::Deserialize;
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct FurnaceConfig {
     #[serde(default)]
     pub lints: LintConfig,
     #[serde(default)]
-    pub ignore: Vec<String>,
-}
-
-impl Default for FurnaceConfig {
-    fn default() -> Self {
-        Self {
-            lints: LintConfig::default(),
-            ignore: vec![],
-        }
-    }
+    pub ignore: Vec<String>, // Gitignore-syntax patterns the engine never reads
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -63,7 +54,8 @@ impl Default for AILintConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+// Lint groups: every lint is disabled until set in `.furnacerc.toml`
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct ComplexityLints {
     pub max_args: Option<usize>,
     pub max_fields: Option<usize>,
@@ -71,7 +63,7 @@ pub struct ComplexityLints {
     pub max_struct_size: Option<usize>, // In number of fields
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct NamingLints {
     pub enforce_snake_case_functions: Option<bool>,
     pub enforce_snake_case_variables: Option<bool>,
@@ -80,42 +72,11 @@ pub struct NamingLints {
     pub discouraged_names: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct StyleLints {
     pub require_doc_comments: Option<bool>,
     pub warn_todo_comments: Option<bool>,
-}
-
-impl Default for ComplexityLints {
-    fn default() -> Self {
-        Self {
-            max_args: None,         // Disabled by default
-            max_fields: None,       // Disabled by default
-            max_function_lines: None,
-            max_struct_size: None,
-        }
-    }
-}
-
-impl Default for NamingLints {
-    fn default() -> Self {
-        Self {
-            enforce_snake_case_functions: None,  // Disabled by default
-            enforce_snake_case_variables: None,  // Disabled by default
-            enforce_pascal_case_types: None,     // Disabled by default
-            enforce_screaming_snake_case_constants: None,
-            discouraged_names: None,             // Disabled by default
-        }
-    }
-}
-
-impl Default for StyleLints {
-    fn default() -> Self {
-        Self {
-            require_doc_comments: None,  // Disabled by default
-            warn_todo_comments: None,    // Disabled by default
-        }
-    }
+    pub warn_orphan_files: Option<bool>, // `.rs` files under `src/` that no `mod` declaration reaches
 }
 
 impl Default for LintConfig {
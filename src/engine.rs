@@ -1,195 +1,1026 @@
-use crate::graph::{ProjectGraph, CrateNode, ModuleNode, FileNode};
-use crate::types::RustFileSnapshot;
-use crate::visitor::SnapshotVisitor;
-use cargo_toml::Manifest;
+use crate::cache::{CachedFile, SnapshotCache};
+use crate::cfg::{CfgContext, FeatureSelection};
+use crate::error::ScanError;
+use crate::ignore_rules::IgnoreRules;
+use crate::calls::CallGraph;
+use crate::symbols::SymbolTable;
+use crate::type_graph::TypeGraph;
+use crate::graph::{ProjectGraph, CrateNode, TargetNode, TargetKind, ModuleNode, FileNode, FileSource, DependencyNode, DependencyKind};
+use crate::types::{ImplRef, Location, RustFileSnapshot};
+use crate::visitor::{docs_of, SnapshotVisitor};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use rayon::prelude::*;
+use cargo_metadata::{Metadata, MetadataCommand, Package};
+use cargo_toml::{Manifest, OptionalFile};
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use syn::parse_file;
 use syn::visit::Visit;
+use walkdir::WalkDir;
+
+/// What to scan for. The default evaluates `cfg`s with each crate's default
+/// features and leaves the target OS open.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub features: FeatureSelection,
+    pub target_os: Option<String>,
+    pub default_members_only: bool, // Skip workspace members outside `default-members`
+    pub cache: bool,                // Reuse parsed files
+    pub cache_dir: Option<PathBuf>, // Where to keep them; defaults to `furnace-cache/` in Cargo's target dir
+    pub ignore: Vec<String>,        // Gitignore-style patterns, on top of `.gitignore` and `.furnaceignore`
+    pub layout: LayoutSource,
+}
+
+/// Where the packages and targets of a Cargo project come from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LayoutSource {
+    /// Parse the `Cargo.toml` files directly.
+    #[default]
+    Manifest,
+    /// Run `cargo metadata --offline --no-deps`, and parse manifests if that fails.
+    CargoMetadata,
+    /// Read saved `cargo metadata --format-version 1` output, and parse manifests if
+    /// it cannot be loaded.
+    MetadataFile(PathBuf),
+}
 
 pub struct TraversalEngine {
     root: PathBuf,
+    options: ScanOptions,
+    cache: Option<SnapshotCache>,
+    ignore: IgnoreRules,
+    errors: Mutex<Vec<ScanError>>, // Collected during a scan, moved into its graph
 }
 
 impl TraversalEngine {
     pub fn new(root: PathBuf) -> Self {
-        Self { root }
+        Self::with_options(root, ScanOptions::default())
     }
 
-    pub fn scan(&self) -> ProjectGraph {
+    pub fn with_options(root: PathBuf, options: ScanOptions) -> Self {
+        // Without a directory to keep it in, the cache is off
+        let cache = options.cache.then(|| options.cache_dir.clone().or_else(|| cache_dir(&root))).flatten().map(SnapshotCache::open);
+        let ignore = IgnoreRules::load(&root, &options.ignore);
+        Self { root, options, cache, ignore, errors: Mutex::new(Vec::new()) }
+    }
+
+    /// Scans the project. Unreadable files, unparseable files and broken member
+    /// manifests are collected in `ProjectGraph::errors`; only a root `Cargo.toml`
+    /// that cannot be loaded fails the whole scan.
+    pub fn try_scan(&self) -> Result<ProjectGraph, ScanError> {
         // 1. Try to find Cargo.toml
         let cargo_path = self.root.join("Cargo.toml");
-        if cargo_path.exists() {
-            self.scan_cargo_project(&cargo_path)
+        let graph = if cargo_path.exists() {
+            match self.load_metadata(&cargo_path) {
+                Some(metadata) => Ok(self.scan_metadata(&metadata)),
+                None => self.scan_cargo_project(&cargo_path),
+            }
         } else {
-            // Fallback: Treat as a single crate rooted at the directory
-            // For now, let's just support Cargo projects for the semantic graph.
-            // Or we could implement a simple recursive scan here.
-            eprintln!("Warning: No Cargo.toml found. Falling back to simple directory scan (not fully implemented in graph mode yet).");
-            ProjectGraph {
-                root_path: self.root.clone(),
-                crates: vec![],
-            }
-        }
-    }
-
-    fn scan_cargo_project(&self, cargo_path: &Path) -> ProjectGraph {
-        let manifest = Manifest::from_path(cargo_path).unwrap_or_else(|_| Manifest { package: None, workspace: None, dependencies: Default::default(), dev_dependencies: Default::default(), build_dependencies: Default::default(), target: Default::default(), features: Default::default(), patch: Default::default(), lib: None, profile: Default::default(), badges: Default::default(), bin: Default::default(), bench: Default::default(), test: Default::default(), example: Default::default(), replace: Default::default(), lints: Default::default() });
-        
-        let mut crates = vec![];
-
-        // Handle workspace members
-        if let Some(workspace) = &manifest.workspace {
-            for member in &workspace.members {
-                // Simple glob expansion would be needed here for full correctness,
-                // but for now assume direct paths or simple wildcards.
-                // We'll just look for subdirectories that match.
-                let member_path = cargo_path.parent().unwrap().join(member);
-                // This is a simplification. Real cargo workspace resolution is complex.
-                // We will assume the member string is a relative path to a crate root.
-                if member_path.exists() {
-                     if let Some(crate_node) = self.scan_crate(&member_path) {
-                        crates.push(crate_node);
-                    }
-                } else {
-                     // Try globbing if it contains *
-                     if member.contains('*') {
-                         // Very basic glob support
-                         let prefix = member.split('*').next().unwrap_or("");
-                         let parent = cargo_path.parent().unwrap().join(prefix);
-                         if parent.exists() {
-                             for entry in fs::read_dir(parent).into_iter().flatten().flatten() {
-                                 if entry.path().is_dir() && entry.path().join("Cargo.toml").exists() {
-                                     if let Some(crate_node) = self.scan_crate(&entry.path()) {
-                                         crates.push(crate_node);
-                                     }
-                                 }
-                             }
-                         }
-                     }
-                }
+            // Fallback: infer crates and modules from the directory layout
+            Ok(self.scan_directory())
+        };
+        if let Some(cache) = &self.cache {
+            // The cache only saves time; a read-only or full disk must not fail the scan
+            let _ = cache.save();
+        }
+
+        // Files are parsed in parallel, so errors arrive in any order
+        let mut errors = std::mem::take(&mut *self.errors.lock().unwrap());
+        errors.sort_by(|a, b| a.path().cmp(b.path()).then_with(|| a.cmp(b)));
+        errors.dedup();
+        let mut graph = graph?;
+        graph.errors = errors;
+        Ok(graph)
+    }
+
+    /// Like `try_scan`, with a failed root manifest as the only error of an empty graph.
+    pub fn scan(&self) -> ProjectGraph {
+        self.try_scan().unwrap_or_else(|err| ProjectGraph {
+            root_path: self.root.clone(),
+            crates: vec![],
+            errors: vec![err],
+            calls: CallGraph::default(),
+            types: TypeGraph::default(),
+        })
+    }
+
+    fn report(&self, err: ScanError) {
+        self.errors.lock().unwrap().push(err);
+    }
+
+    /// Builds synthetic crates for a tree of `.rs` files without a manifest. Every
+    /// topmost directory holding `.rs` files becomes a crate, named after the directory
+    /// (or its parent, for a bare `src/`), with a single lib or bin target.
+    fn scan_directory(&self) -> ProjectGraph {
+        let layout = DirectoryLayout::walk(&self.root, &self.ignore);
+        let cfg = CfgContext::resolve(&Default::default(), &[], &self.options.features, self.options.target_os.clone());
+
+        let crates = layout.crate_roots().into_par_iter().map(|crate_dir| {
+            let dir_name = |dir: &Path| dir.file_name().map(|name| name.to_string_lossy().to_string());
+            let name = match dir_name(&crate_dir) {
+                Some(name) if name == "src" => crate_dir.parent().and_then(dir_name),
+                name => name,
             }
-        } else if let Some(_package) = &manifest.package {
-            // Single package project
-            if let Some(crate_node) = self.scan_crate(cargo_path.parent().unwrap()) {
-                crates.push(crate_node);
+            .or_else(|| fs::canonicalize(&crate_dir).ok().as_deref().and_then(dir_name))
+            .unwrap_or_else(|| "crate".to_string());
+
+            let root_file = ["lib.rs", "main.rs", "mod.rs"].iter()
+                .map(|file| crate_dir.join(file))
+                .find(|file| layout.contains(file));
+            let kind = match root_file.as_ref().and_then(|file| file.file_name()) {
+                Some(file) if file == "main.rs" => TargetKind::Bin,
+                _ => TargetKind::Lib,
+            };
+            let root_module = self.scan_layout_module("crate", &crate_dir, root_file.as_deref(), &layout, &cfg);
+
+            let crate_node = CrateNode {
+                name: name.clone(),
+                version: "0.0.0".to_string(),
+                path: crate_dir,
+                targets: vec![TargetNode { name, kind, root_module, symbols: SymbolTable::default() }],
+                orphans: vec![],
+                dependencies: vec![],
+                default_member: true,
+            };
+            (crate_node, cfg.clone())
+        }).collect();
+
+        self.build_graph(crates)
+    }
+
+    fn scan_cargo_project(&self, cargo_path: &Path) -> Result<ProjectGraph, ScanError> {
+        let manifest = Manifest::from_path(cargo_path).map_err(|err| ScanError::manifest(cargo_path, err))?;
+
+        let root_dir = cargo_path.parent().unwrap_or(&self.root);
+        let crates = workspace_members(root_dir, &manifest).into_par_iter()
+            .filter(|(_, default_member)| !self.options.default_members_only || *default_member)
+            .filter(|(crate_dir, _)| !self.ignore.is_ignored(crate_dir, true))
+            .filter_map(|(crate_dir, default_member)| {
+                let (mut crate_node, cfg) = self.scan_crate(&crate_dir)?;
+                crate_node.default_member = default_member;
+                Some((crate_node, cfg))
+            })
+            .collect();
+
+        Ok(self.build_graph(crates))
+    }
+
+    /// `cargo metadata` output for the project, if `options.layout` asks for it.
+    fn load_metadata(&self, cargo_path: &Path) -> Option<Metadata> {
+        match &self.options.layout {
+            LayoutSource::Manifest => None,
+            // No cargo on the PATH, or a workspace cargo rejects: the manifest parser takes over
+            LayoutSource::CargoMetadata => MetadataCommand::new()
+                .manifest_path(cargo_path)
+                .no_deps()
+                .other_options(vec!["--offline".to_string()])
+                .exec()
+                .ok(),
+            LayoutSource::MetadataFile(path) => {
+                let json = fs::read_to_string(path).map_err(|err| self.report(ScanError::io(path, &err))).ok()?;
+                MetadataCommand::parse(json).map_err(|err| self.report(ScanError::manifest(path, err))).ok()
+            }
+        }
+    }
+
+    /// Builds crates from `cargo metadata`: the workspace members with cargo's own
+    /// targets, feature tables and default-members.
+    fn scan_metadata(&self, metadata: &Metadata) -> ProjectGraph {
+        // Cargo reports absolute paths; keep them under `self.root` like the manifest parser
+        let canonical_root = fs::canonicalize(&self.root).unwrap_or_else(|_| self.root.clone());
+        let relocate = |path: &Path| match path.strip_prefix(&canonical_root) {
+            Ok(relative) => self.root.join(relative),
+            Err(_) => path.to_path_buf(),
+        };
+        let is_default_member = |package: &Package| {
+            let defaults = &metadata.workspace_default_members;
+            defaults.is_missing() || defaults.contains(&package.id)
+        };
+
+        let crates = metadata.workspace_packages().into_par_iter()
+            .filter(|package| !self.options.default_members_only || is_default_member(package))
+            .filter_map(|package| {
+                let crate_root = relocate(package.manifest_path.parent()?.as_std_path());
+                if self.ignore.is_ignored(&crate_root, true) {
+                    return None;
+                }
+                let optional_deps: Vec<String> = package.dependencies.iter()
+                    .filter(|dep| dep.optional)
+                    .map(|dep| dep.rename.clone().unwrap_or_else(|| dep.name.clone()))
+                    .collect();
+                let cfg = CfgContext::resolve(&package.features, &optional_deps, &self.options.features, self.options.target_os.clone());
+
+                let roots = package.targets.iter()
+                    .filter_map(|target| {
+                        let kind = target.kind.iter().find_map(target_kind)?;
+                        Some((target.name.clone(), kind, relocate(target.src_path.as_std_path())))
+                    })
+                    .collect();
+                let (mut crate_node, cfg) = self.scan_targets(package.name.clone(), package.version.to_string(), &crate_root, roots, cfg)?;
+                crate_node.default_member = is_default_member(package);
+                crate_node.dependencies = package.dependencies.iter().map(|dep| metadata_dependency(dep, relocate)).collect();
+                Some((crate_node, cfg))
+            })
+            .collect();
+
+        self.build_graph(crates)
+    }
+
+    /// Second phase of a scan: parses every file the discovered module trees reach,
+    /// in parallel, then links impls across them. Each crate comes with the `cfg` its
+    /// files are evaluated against.
+    fn build_graph(&self, crates: Vec<(CrateNode, CfgContext)>) -> ProjectGraph {
+        let (mut crates, cfgs): (Vec<CrateNode>, Vec<CfgContext>) = crates.into_iter().unzip();
+        link_dependencies(&mut crates);
+
+        let mut files: Vec<(&CfgContext, &mut FileNode)> = Vec::new();
+        for (crate_node, cfg) in crates.iter_mut().zip(&cfgs) {
+            let mut crate_files = Vec::new();
+            for target in &mut crate_node.targets {
+                collect_files_mut(&mut target.root_module, &mut crate_files);
             }
+            crate_files.extend(crate_node.orphans.iter_mut());
+            files.extend(crate_files.into_iter().map(|file| (cfg, file)));
         }
+        files.into_par_iter().for_each(|(cfg, file)| file.snapshot = self.load_snapshot(file, cfg));
 
-        ProjectGraph {
+        let mut graph = ProjectGraph {
             root_path: self.root.clone(),
             crates,
-        }
+            errors: vec![],
+            calls: CallGraph::default(),
+            types: TypeGraph::default(),
+        };
+        link_impls(&mut graph);
+        graph.crates.par_iter_mut()
+            .flat_map(|crate_node| crate_node.targets.par_iter_mut())
+            .for_each(|target| target.symbols = SymbolTable::build(&target.root_module));
+        graph.calls = CallGraph::build(&graph);
+        graph.types = TypeGraph::build(&graph);
+        graph
     }
 
-    fn scan_crate(&self, crate_root: &Path) -> Option<CrateNode> {
+    /// First phase of a scan: the crate's targets and their module trees, with files
+    /// discovered but not parsed yet.
+    fn scan_crate(&self, crate_root: &Path) -> Option<(CrateNode, CfgContext)> {
         let cargo_path = crate_root.join("Cargo.toml");
-        let manifest = Manifest::from_path(&cargo_path).ok()?;
-        let package = manifest.package?;
+        let manifest = match Manifest::from_path(&cargo_path) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                self.report(ScanError::manifest(&cargo_path, err));
+                return None;
+            }
+        };
+        let optional_deps: Vec<String> = manifest.dependencies.iter()
+            .filter(|(_, dep)| dep.optional())
+            .map(|(name, _)| name.clone())
+            .collect();
+        let cfg = CfgContext::resolve(&manifest.features, &optional_deps, &self.options.features, self.options.target_os.clone());
+        let Some(package) = manifest.package.as_ref() else {
+            self.report(ScanError::manifest(&cargo_path, "no [package] section"));
+            return None;
+        };
 
-        let name = package.name;
+        let name = package.name.clone();
         let version = package.version.get().map(|v| v.to_string()).unwrap_or_else(|_| "0.0.0".to_string());
 
-        // Find entry point (lib.rs or main.rs)
-        let src_path = crate_root.join("src");
-        let lib_rs = src_path.join("lib.rs");
-        let main_rs = src_path.join("main.rs");
+        // Crate roots of every target. `Manifest::from_path` has already filled in the
+        // ones cargo infers from the layout (`src/main.rs`, `src/bin/*`, `tests/*`, ...)
+        let mut roots: Vec<(String, TargetKind, PathBuf)> = Vec::new();
+        if let Some(lib) = &manifest.lib {
+            if let Some(path) = &lib.path {
+                let lib_name = lib.name.clone().unwrap_or_else(|| name.replace('-', "_"));
+                roots.push((lib_name, TargetKind::Lib, crate_root.join(path)));
+            }
+        }
+        let products = [
+            (&manifest.bin, TargetKind::Bin),
+            (&manifest.example, TargetKind::Example),
+            (&manifest.test, TargetKind::Test),
+            (&manifest.bench, TargetKind::Bench),
+        ];
+        for (products, kind) in products {
+            for product in products {
+                if let (Some(product_name), Some(path)) = (&product.name, &product.path) {
+                    roots.push((product_name.clone(), kind, crate_root.join(path)));
+                }
+            }
+        }
+        if let Some(OptionalFile::Path(path)) = &package.build {
+            roots.push(("build-script-build".to_string(), TargetKind::BuildScript, crate_root.join(path)));
+        }
 
-        let root_file = if lib_rs.exists() {
-            Some(lib_rs)
-        } else if main_rs.exists() {
-            Some(main_rs)
-        } else {
-            None
-        };
+        let (mut crate_node, cfg) = self.scan_targets(name, version, crate_root, roots, cfg)?;
+        crate_node.dependencies = manifest_dependencies(crate_root, &manifest);
+        Some((crate_node, cfg))
+    }
 
-        if let Some(root_file) = root_file {
-            let root_module = self.scan_module("crate", &root_file, &src_path);
-            Some(CrateNode {
-                name,
-                version,
-                path: crate_root.to_path_buf(),
-                root_module,
+    /// A crate with one target per existing, non-ignored root in `roots`, ordered lib,
+    /// bins, examples, tests, benches, build script. None if no root is left.
+    fn scan_targets(&self, name: String, version: String, crate_root: &Path, mut roots: Vec<(String, TargetKind, PathBuf)>, cfg: CfgContext) -> Option<(CrateNode, CfgContext)> {
+        roots.sort_by_key(|(_, kind, _)| *kind);
+
+        // Every target root is a `mod.rs`-like file: its modules live next to it
+        let mut gated = Vec::new();
+        let targets: Vec<TargetNode> = roots.into_iter()
+            .filter(|(_, _, root_file)| root_file.exists() && !self.ignore.is_ignored(root_file, false))
+            .map(|(target_name, kind, root_file)| {
+                let module_dir = root_file.parent().unwrap_or(crate_root).to_path_buf();
+                TargetNode {
+                    name: target_name,
+                    kind,
+                    root_module: self.scan_module("crate", &root_file, &module_dir, None, &cfg, &mut gated),
+                    symbols: SymbolTable::default(),
+                }
             })
-        } else {
-            None
+            .collect();
+
+        if targets.is_empty() {
+            return None;
         }
+        let orphans = self.find_orphans(&crate_root.join("src"), &targets, &gated);
+        let crate_node = CrateNode {
+            name,
+            version,
+            path: crate_root.to_path_buf(),
+            targets,
+            orphans,
+            dependencies: vec![],
+            default_member: true,
+        };
+        Some((crate_node, cfg))
     }
 
-    fn scan_module(&self, name: &str, file_path: &Path, search_dir: &Path) -> ModuleNode {
-        let file_node = self.create_file_node(file_path);
-        let mut submodules = vec![];
+    /// Scans the module defined by `file_path`. Its `mod foo;` children are looked up
+    /// in `module_dir`: the file's own directory for `lib.rs`, `main.rs`, `mod.rs` and
+    /// `#[path]` files, `<dir>/<name>/` for any other `name.rs`.
+    fn scan_module(&self, name: &str, file_path: &Path, module_dir: &Path, outer_docs: Option<String>, cfg: &CfgContext, gated: &mut Vec<PathBuf>) -> ModuleNode {
+        let (file_node, module_items) = self.discover_file(file_path, cfg);
 
-        // Parse the file to find `mod xyz;` declarations
-        if let Some(_snapshot) = &file_node.snapshot {
-             let content = fs::read_to_string(file_path).unwrap_or_default();
-             if let Ok(ast) = parse_file(&content) {
-                 for item in ast.items {
-                     if let syn::Item::Mod(item_mod) = item {
-                         let mod_name = item_mod.ident.to_string();
-                         
-                         if item_mod.content.is_none() {
-                             // Look for the file
-                             let p1 = search_dir.join(format!("{}.rs", mod_name));
-                             let p2 = search_dir.join(&mod_name).join("mod.rs");
-                             
-                             if p1.exists() {
-                                 submodules.push(self.scan_module(&mod_name, &p1, search_dir));
-                             } else if p2.exists() {
-                                 submodules.push(self.scan_module(&mod_name, &p2, &search_dir.join(&mod_name)));
-                             }
-                         }
-                     }
-                 }
-             }
-        }
+        // Follow the file's `mod xyz;` declarations. A file switched off by
+        // `#![cfg(..)]` declares no modules at all
+        let submodules = if cfg.is_active(&module_items.attrs) {
+            let file_dir = file_path.parent().unwrap_or(module_dir);
+            self.scan_mod_items(&module_items.items, module_dir, file_dir, cfg, gated)
+        } else {
+            gated.push(module_dir.to_path_buf());
+            vec![]
+        };
+
+        let inner_docs = docs_of(&module_items.attrs);
+        let docs = match (outer_docs, inner_docs) {
+            (Some(outer), Some(inner)) => Some(format!("{}\n{}", outer, inner)),
+            (outer, inner) => outer.or(inner),
+        };
 
         ModuleNode {
             name: name.to_string(),
-            path: Some(search_dir.to_path_buf()),
+            path: Some(module_dir.to_path_buf()),
             file: Some(file_node),
+            docs,
             submodules,
         }
     }
 
-    fn create_file_node(&self, path: &Path) -> FileNode {
-        let content = fs::read_to_string(path).unwrap_or_default();
-        let mut hasher = Sha256::new();
-        hasher.update(&content);
-        let hash = hex::encode(hasher.finalize());
-
-        // Parse content (Content Phase)
-        // In a real incremental engine, we would check the cache here.
-        let snapshot = if let Ok(file) = parse_file(&content) {
-            let mut visitor = SnapshotVisitor::default();
-            visitor.visit_file(&file);
-            
-            // Associate impls (local)
-            for impl_snap in &visitor.impls {
-                if let Some(struct_idx) = visitor.structs.iter().position(|s| s.name == impl_snap.for_type) {
-                    visitor.structs[struct_idx].methods.extend(impl_snap.methods.clone());
-                } else if let Some(enum_idx) = visitor.enums.iter().position(|e| e.name == impl_snap.for_type) {
-                    visitor.enums[enum_idx].methods.extend(impl_snap.methods.clone());
+    /// Module nodes for the `mod` items among `items`. Children are looked up in `dir`;
+    /// `#[path]` attributes resolve against `path_base`, which is the directory of the
+    /// source file at the top level and `dir` itself inside inline modules.
+    /// `items` are not cfg-stripped: modules that are switched off are skipped, and the
+    /// files and directories they would have claimed are pushed to `gated` so they are
+    /// not mistaken for orphans.
+    fn scan_mod_items(&self, items: &[syn::Item], dir: &Path, path_base: &Path, cfg: &CfgContext, gated: &mut Vec<PathBuf>) -> Vec<ModuleNode> {
+        let mut submodules = vec![];
+        for item in items {
+            let syn::Item::Mod(item_mod) = item else { continue };
+            let mut attrs = item_mod.attrs.clone();
+            cfg.expand_cfg_attr(&mut attrs);
+            let active = cfg.is_active(&attrs);
+            let mod_name = item_mod.ident.to_string();
+            let mod_docs = docs_of(&attrs);
+            let path_attr = path_attr_of(&attrs);
+
+            if let Some((_, inline_items)) = &item_mod.content {
+                // Inline modules own a directory for their children but no file; their
+                // items are part of the enclosing file's snapshot
+                let child_dir = match &path_attr {
+                    Some(path) => path_base.join(path),
+                    None => dir.join(&mod_name),
+                };
+                if !active {
+                    gated.push(child_dir);
+                    continue;
+                }
+                submodules.push(ModuleNode {
+                    name: mod_name,
+                    path: Some(child_dir.clone()),
+                    file: None,
+                    docs: mod_docs,
+                    submodules: self.scan_mod_items(inline_items, &child_dir, &child_dir, cfg, gated),
+                });
+                continue;
+            }
+
+            let (file, module_dir) = match &path_attr {
+                // Files loaded through `#[path]` behave like `mod.rs` for their own children
+                Some(path) => {
+                    let file = path_base.join(path);
+                    let module_dir = file.parent().unwrap_or(path_base).to_path_buf();
+                    (file, module_dir)
                 }
+                None => {
+                    let flat = dir.join(format!("{}.rs", mod_name));
+                    let nested = dir.join(&mod_name).join("mod.rs");
+                    (if flat.exists() { flat } else { nested }, dir.join(&mod_name))
+                }
+            };
+            if !active {
+                // A `#[path]` file's directory may hold unrelated modules, so only the file is gated
+                if path_attr.is_none() {
+                    gated.push(module_dir);
+                }
+                gated.push(file);
+                continue;
+            }
+            // Ignored files are left out of the tree without being read
+            if file.exists() && !self.ignore.is_ignored(&file, false) {
+                submodules.push(self.scan_module(&mod_name, &file, &module_dir, mod_docs, cfg, gated));
             }
+        }
+        submodules
+    }
+
+    /// `.rs` files under `src_dir` that no target's module tree reaches, other than
+    /// those claimed by a module that is switched off by `cfg`.
+    fn find_orphans(&self, src_dir: &Path, targets: &[TargetNode], gated: &[PathBuf]) -> Vec<FileNode> {
+        let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut files = Vec::new();
+        for target in targets {
+            collect_files(&target.root_module, &mut files);
+        }
+        let reached: BTreeSet<PathBuf> = files.iter().map(|file| canonical(&file.path)).collect();
+        let gated: Vec<PathBuf> = gated.iter().map(|path| canonical(path)).collect();
 
-            Some(RustFileSnapshot {
-                path: path.to_string_lossy().to_string(),
-                functions: visitor.functions,
-                structs: visitor.structs,
-                traits: visitor.traits,
-                enums: visitor.enums,
-                impls: visitor.impls,
+        let layout = DirectoryLayout::walk(src_dir, &self.ignore);
+        layout.files.values().flatten()
+            .filter(|file| {
+                let file = canonical(file);
+                !reached.contains(&file) && !gated.iter().any(|gate| file.starts_with(gate))
             })
-        } else {
-            None
+            .map(|file| {
+                let content = fs::read_to_string(file).map_err(|err| self.report(ScanError::io(file, &err))).ok();
+                let hash = content.as_deref().map(hash_content).unwrap_or_default();
+                let source = content.map(|content| FileSource { content, module_items: None });
+                FileNode { path: file.clone(), hash, snapshot: None, source }
+            })
+            .collect()
+    }
+
+    /// Module for `dir`, defined by `own_file` if there is one. Each other `.rs` file in
+    /// `dir` is a child module, as is each subdirectory without a sibling `<name>.rs`;
+    /// `foo.rs` next to `foo/` takes that directory's files as its children.
+    fn scan_layout_module(&self, name: &str, dir: &Path, own_file: Option<&Path>, layout: &DirectoryLayout, cfg: &CfgContext) -> ModuleNode {
+        let mut submodules = vec![];
+        for file in layout.files_in(dir) {
+            if Some(file.as_path()) == own_file {
+                continue;
+            }
+            let Some(stem) = file.file_stem().map(|stem| stem.to_string_lossy().to_string()) else { continue };
+            let child_dir = dir.join(&stem);
+            if layout.has_dir(&child_dir) {
+                submodules.push(self.scan_layout_module(&stem, &child_dir, Some(file), layout, cfg));
+            } else {
+                let (file_node, module_items) = self.discover_file(file, cfg);
+                submodules.push(ModuleNode {
+                    name: stem,
+                    path: Some(child_dir),
+                    docs: docs_of(&module_items.attrs),
+                    file: Some(file_node),
+                    submodules: vec![],
+                });
+            }
+        }
+        for subdir in layout.subdirs_of(dir) {
+            let Some(sub_name) = subdir.file_name().map(|name| name.to_string_lossy().to_string()) else { continue };
+            if layout.contains(&dir.join(format!("{}.rs", sub_name))) {
+                continue;
+            }
+            let mod_rs = subdir.join("mod.rs");
+            let own = layout.contains(&mod_rs).then_some(mod_rs.as_path());
+            submodules.push(self.scan_layout_module(&sub_name, subdir, own, layout, cfg));
+        }
+
+        let (file, docs) = match own_file {
+            Some(file) => {
+                let (file_node, module_items) = self.discover_file(file, cfg);
+                (Some(file_node), docs_of(&module_items.attrs))
+            }
+            None => (None, None),
+        };
+        ModuleNode {
+            name: name.to_string(),
+            path: Some(dir.to_path_buf()),
+            docs,
+            file,
+            submodules,
+        }
+    }
+
+    /// Node for the file at `path`, not parsed yet, and its inner attributes and `mod`
+    /// items with `cfg_attr` expanded on the former. Unchanged files take those from
+    /// the cache; others are only lexed here, and keep their content and module items
+    /// for the parse phase.
+    fn discover_file(&self, path: &Path, cfg: &CfgContext) -> (FileNode, syn::File) {
+        // An unreadable file keeps an empty hash, which tells `load_snapshot` to skip it
+        let (content, hash) = match fs::read_to_string(path) {
+            Ok(content) => {
+                let hash = hash_content(&content);
+                (content, hash)
+            }
+            Err(err) => {
+                self.report(ScanError::io(path, &err));
+                (String::new(), String::new())
+            }
         };
 
-        FileNode {
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(&SnapshotCache::key(&hash, path, cfg)));
+        let (module_items, content) = match cached {
+            Some(entry) => (entry.module_items, None),
+            None => (module_items_of(&content), Some(content)),
+        };
+        let mut items = parse_file(&module_items).unwrap_or_else(|_| syn::File { shebang: None, attrs: vec![], items: vec![] });
+        cfg.expand_cfg_attr(&mut items.attrs);
+
+        let file_node = FileNode {
             path: path.to_path_buf(),
             hash,
-            snapshot,
+            snapshot: None,
+            source: content.map(|content| FileSource { content, module_items: Some(module_items) }),
+        };
+        (file_node, items)
+    }
+
+    /// Snapshot of a discovered file, from the cache when its hash is known there, else
+    /// parsed from the content read at discovery, which `hash` was computed from.
+    fn load_snapshot(&self, file: &mut FileNode, cfg: &CfgContext) -> Option<RustFileSnapshot> {
+        if file.hash.is_empty() {
+            return None;
+        }
+        let key = self.cache.as_ref().map(|_| SnapshotCache::key(&file.hash, &file.path, cfg));
+        if let Some(entry) = self.cache.as_ref().zip(key.as_deref()).and_then(|(cache, key)| cache.get(key)) {
+            return Some(entry.snapshot);
+        }
+
+        let source = file.source.take()?;
+        // Files that fail to parse are not cached, so their error is reported on every scan
+        let snapshot = match parse_snapshot(&file.path, &source.content, cfg) {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                self.report(ScanError::parse(&file.path, &err));
+                return None;
+            }
+        };
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            let module_items = source.module_items.unwrap_or_else(|| module_items_of(&source.content));
+            cache.insert(key, CachedFile { snapshot: snapshot.clone(), module_items });
         }
+        Some(snapshot)
+    }
+}
+
+/// Default cache directory for a scan of `root`: `furnace-cache/` in the Cargo target
+/// directory, which `CARGO_TARGET_DIR` overrides. None for a tree without a manifest,
+/// so no `target/` appears in it.
+fn cache_dir(root: &Path) -> Option<PathBuf> {
+    if !root.join("Cargo.toml").exists() {
+        return None;
+    }
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR").filter(|dir| !dir.is_empty()).map_or_else(|| root.join("target"), PathBuf::from);
+    Some(target_dir.join("furnace-cache"))
+}
+
+fn hash_content(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content);
+    hex::encode(hasher.finalize())
+}
+
+/// Parses `content` and strips what `cfg` switches off.
+fn parse_snapshot(path: &Path, content: &str, cfg: &CfgContext) -> syn::Result<RustFileSnapshot> {
+    let mut file = parse_file(content)?;
+    cfg.strip_file(&mut file);
+    let mut visitor = SnapshotVisitor::default();
+    visitor.visit_file(&file);
+
+    Ok(RustFileSnapshot {
+        path: path.to_string_lossy().to_string(),
+        docs: visitor.docs,
+        modules: visitor.modules,
+        functions: visitor.functions,
+        structs: visitor.structs,
+        traits: visitor.traits,
+        enums: visitor.enums,
+        impls: visitor.impls,
+        consts: visitor.consts,
+        statics: visitor.statics,
+        type_aliases: visitor.type_aliases,
+        unions: visitor.unions,
+        macros: visitor.macros,
+        extern_blocks: visitor.extern_blocks,
+        uses: visitor.uses,
+    })
+}
+
+/// The source's inner attributes and (nested) `mod` items, found by lexing alone so
+/// module discovery does not pay for a full parse. Inline modules keep only their own
+/// `mod` items. Empty if `source` does not lex.
+fn module_items_of(source: &str) -> String {
+    // A shebang line is not Rust tokens; `#![attr]` on the first line is
+    let source = match source.strip_prefix("#!") {
+        Some(rest) if !rest.trim_start().starts_with('[') => rest.split_once('\n').map_or("", |(_, rest)| rest),
+        _ => source,
+    };
+    match source.parse::<TokenStream>() {
+        Ok(tokens) => keep_module_items(tokens).to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+fn keep_module_items(tokens: TokenStream) -> TokenStream {
+    let is_punct = |token: Option<&TokenTree>, ch: char| matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ch);
+    let is_ident = |token: Option<&TokenTree>, name: &str| matches!(token, Some(TokenTree::Ident(ident)) if ident == name);
+    let is_group = |token: Option<&TokenTree>, delimiter: Delimiter| matches!(token, Some(TokenTree::Group(group)) if group.delimiter() == delimiter);
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut kept = TokenStream::new();
+
+    // Inner attributes (`#![..]`, `//!`) lead the file or module
+    let mut start = 0;
+    while is_punct(tokens.get(start), '#') && is_punct(tokens.get(start + 1), '!') && is_group(tokens.get(start + 2), Delimiter::Bracket) {
+        start += 3;
+    }
+    kept.extend(tokens[..start].iter().cloned());
+
+    // `mod` is a keyword, so at the top level it only ever starts a module item
+    for idx in start..tokens.len() {
+        if !is_ident(tokens.get(idx), "mod") || !matches!(tokens.get(idx + 1), Some(TokenTree::Ident(_))) {
+            continue;
+        }
+        let body = match tokens.get(idx + 2) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                let mut inner = Group::new(Delimiter::Brace, keep_module_items(group.stream()));
+                inner.set_span(group.span());
+                TokenTree::Group(inner)
+            }
+            token if is_punct(token, ';') => tokens[idx + 2].clone(),
+            _ => continue,
+        };
+
+        // Walk back over `unsafe`, the visibility and the outer attributes
+        let mut first = idx;
+        if first > start && is_ident(tokens.get(first - 1), "unsafe") {
+            first -= 1;
+        }
+        if first > start + 1 && is_group(tokens.get(first - 1), Delimiter::Parenthesis) && is_ident(tokens.get(first - 2), "pub") {
+            first -= 2;
+        } else if first > start && is_ident(tokens.get(first - 1), "pub") {
+            first -= 1;
+        }
+        while first > start + 1 && is_group(tokens.get(first - 1), Delimiter::Bracket) && is_punct(tokens.get(first - 2), '#') {
+            first -= 2;
+        }
+
+        kept.extend(tokens[first..idx + 2].iter().cloned());
+        kept.extend([body]);
+    }
+    kept
+}
+
+/// The `.rs` files under a directory, indexed by directory. Hidden directories,
+/// `target/` and ignored paths are skipped.
+struct DirectoryLayout {
+    files: BTreeMap<PathBuf, Vec<PathBuf>>,     // dir -> `.rs` files directly inside, sorted
+    subdirs: BTreeMap<PathBuf, BTreeSet<PathBuf>>, // dir -> child dirs with `.rs` files somewhere below
+}
+
+impl DirectoryLayout {
+    fn walk(root: &Path, ignore: &IgnoreRules) -> Self {
+        let mut layout = DirectoryLayout { files: BTreeMap::new(), subdirs: BTreeMap::new() };
+        let walker = WalkDir::new(root).sort_by_file_name().into_iter().filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            let is_dir = entry.file_type().is_dir();
+            entry.depth() == 0 || !((is_dir && (name.starts_with('.') || name == "target")) || ignore.is_ignored(entry.path(), is_dir))
+        });
+        for entry in walker.flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Some(dir) = path.parent() else { continue };
+            layout.files.entry(dir.to_path_buf()).or_default().push(path.to_path_buf());
+
+            // Register the directory chain up to the root
+            let mut child = dir;
+            while let Some(parent) = child.parent().filter(|_| child != root) {
+                if !layout.subdirs.entry(parent.to_path_buf()).or_default().insert(child.to_path_buf()) {
+                    break;
+                }
+                child = parent;
+            }
+        }
+        layout
+    }
+
+    /// Topmost directories that directly contain `.rs` files.
+    fn crate_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for dir in self.files.keys() {
+            if !roots.iter().any(|root| dir.starts_with(root)) {
+                roots.push(dir.clone());
+            }
+        }
+        roots
+    }
+
+    fn contains(&self, file: &Path) -> bool {
+        file.parent().is_some_and(|dir| self.files_in(dir).iter().any(|known| known == file))
+    }
+
+    fn has_dir(&self, dir: &Path) -> bool {
+        self.files.contains_key(dir) || self.subdirs.contains_key(dir)
+    }
+
+    fn files_in(&self, dir: &Path) -> &[PathBuf] {
+        self.files.get(dir).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn subdirs_of(&self, dir: &Path) -> impl Iterator<Item = &PathBuf> {
+        self.subdirs.get(dir).into_iter().flatten()
+    }
+}
+
+/// Dependencies declared in `manifest`, which lives in `crate_root`.
+fn manifest_dependencies(crate_root: &Path, manifest: &Manifest) -> Vec<DependencyNode> {
+    let mut tables = vec![
+        (&manifest.dependencies, DependencyKind::Normal),
+        (&manifest.dev_dependencies, DependencyKind::Dev),
+        (&manifest.build_dependencies, DependencyKind::Build),
+    ];
+    for target in manifest.target.values() {
+        tables.push((&target.dependencies, DependencyKind::Normal));
+        tables.push((&target.dev_dependencies, DependencyKind::Dev));
+        tables.push((&target.build_dependencies, DependencyKind::Build));
+    }
+
+    let mut dependencies = Vec::new();
+    for (table, kind) in tables {
+        for (name, dep) in table {
+            let detail = dep.detail();
+            dependencies.push(DependencyNode {
+                name: name.clone(),
+                package: dep.package().unwrap_or(name).to_string(),
+                kind,
+                requirement: match dep {
+                    cargo_toml::Dependency::Simple(requirement) => Some(requirement.clone()),
+                    _ => detail.and_then(|detail| detail.version.clone()),
+                },
+                path: detail.and_then(|detail| detail.path.as_ref()).map(|path| crate_root.join(path)),
+                optional: dep.optional(),
+                workspace_crate: None,
+            });
+        }
+    }
+    dependencies
+}
+
+/// A dependency as `cargo metadata` reports it, with its path moved by `relocate`.
+fn metadata_dependency(dep: &cargo_metadata::Dependency, relocate: impl Fn(&Path) -> PathBuf) -> DependencyNode {
+    let path = dep.path.as_ref().map(|path| relocate(path.as_std_path()));
+    // Cargo reports `*` for dependencies that did not state a version
+    let stated = !(path.is_some() && dep.req == cargo_metadata::semver::VersionReq::STAR);
+    DependencyNode {
+        name: dep.rename.clone().unwrap_or_else(|| dep.name.clone()),
+        package: dep.name.clone(),
+        kind: match dep.kind {
+            cargo_metadata::DependencyKind::Development => DependencyKind::Dev,
+            cargo_metadata::DependencyKind::Build => DependencyKind::Build,
+            _ => DependencyKind::Normal,
+        },
+        requirement: stated.then(|| dep.req.to_string()),
+        path,
+        optional: dep.optional,
+        workspace_crate: None,
+    }
+}
+
+/// Points path dependencies at the crates of the graph living in their directory.
+fn link_dependencies(crates: &mut [CrateNode]) {
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let by_dir: HashMap<PathBuf, usize> = crates.iter().enumerate()
+        .map(|(idx, crate_node)| (canonical(&crate_node.path), idx))
+        .collect();
+    for crate_node in crates.iter_mut() {
+        for dep in &mut crate_node.dependencies {
+            dep.workspace_crate = dep.path.as_deref().and_then(|path| by_dir.get(&canonical(path)).copied());
+        }
+    }
+}
+
+/// Our kind for one of the kinds cargo lists for a target. Every library crate type
+/// is a `Lib`.
+fn target_kind(kind: &cargo_metadata::TargetKind) -> Option<TargetKind> {
+    use cargo_metadata::TargetKind as Cargo;
+    match kind {
+        Cargo::Lib | Cargo::RLib | Cargo::DyLib | Cargo::CDyLib | Cargo::StaticLib | Cargo::ProcMacro => Some(TargetKind::Lib),
+        Cargo::Bin => Some(TargetKind::Bin),
+        Cargo::Example => Some(TargetKind::Example),
+        Cargo::Test => Some(TargetKind::Test),
+        Cargo::Bench => Some(TargetKind::Bench),
+        Cargo::CustomBuild => Some(TargetKind::BuildScript),
+        _ => None,
+    }
+}
+
+/// Crate directories of a manifest, each with whether it is a default member.
+///
+/// Follows cargo: the root package comes first, `members` globs are expanded and
+/// directories without a `Cargo.toml` skipped, and anything under an `exclude` path is
+/// dropped. Without `default-members`, the root package alone is the default member,
+/// or every member when the workspace is virtual.
+fn workspace_members(root_dir: &Path, manifest: &Manifest) -> Vec<(PathBuf, bool)> {
+    let Some(workspace) = &manifest.workspace else {
+        return match manifest.package {
+            Some(_) => vec![(root_dir.to_path_buf(), true)],
+            None => vec![],
+        };
+    };
+
+    // Canonical paths only serve comparisons; scanned paths stay as the user wrote them
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let expand = |patterns: &[String]| -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for pattern in patterns {
+            let full = format!("{}/{}", glob::Pattern::escape(&root_dir.to_string_lossy()), pattern);
+            for dir in glob::glob(&full).into_iter().flatten().flatten() {
+                if dir.join("Cargo.toml").is_file() && !dirs.iter().any(|seen| canonical(seen) == canonical(&dir)) {
+                    dirs.push(dir);
+                }
+            }
+        }
+        dirs
+    };
+    let excluded: Vec<PathBuf> = workspace.exclude.iter().map(|path| canonical(&root_dir.join(path))).collect();
+
+    let mut members = Vec::new();
+    if manifest.package.is_some() {
+        members.push(root_dir.to_path_buf());
+    }
+    for dir in expand(&workspace.members) {
+        let dir_key = canonical(&dir);
+        let is_root = dir_key == canonical(root_dir);
+        if !is_root && !excluded.iter().any(|exclude| dir_key.starts_with(exclude)) {
+            members.push(dir);
+        }
+    }
+
+    let defaults: Vec<PathBuf> = if !workspace.default_members.is_empty() {
+        expand(&workspace.default_members).iter().map(|dir| canonical(dir)).collect()
+    } else if manifest.package.is_some() {
+        vec![canonical(root_dir)]
+    } else {
+        members.iter().map(|dir| canonical(dir)).collect()
+    };
+
+    members.into_iter()
+        .map(|dir| {
+            let default_member = defaults.contains(&canonical(&dir));
+            (dir, default_member)
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum TypeKind {
+    Struct,
+    Enum,
+}
+
+/// Links every impl block to the struct or enum it implements, across files and crates.
+///
+/// Types are matched by base name. When several definitions share a name, the impl's
+/// own file wins, then its directory (sibling modules) within the same target, then a
+/// definition that is unique within the impl's target, its crate, and finally the whole
+/// graph. Impls that remain ambiguous, or target types outside the graph, are left unlinked.
+fn link_impls(graph: &mut ProjectGraph) {
+    // Files reachable from several targets of a crate appear once per target
+    let mut files: Vec<((usize, usize), &mut RustFileSnapshot)> = Vec::new();
+    for (crate_idx, crate_node) in graph.crates.iter_mut().enumerate() {
+        for (target_idx, target) in crate_node.targets.iter_mut().enumerate() {
+            let mut target_files = Vec::new();
+            collect_snapshots_mut(&mut target.root_module, &mut target_files);
+            files.extend(target_files.into_iter().map(|snapshot| ((crate_idx, target_idx), snapshot)));
+        }
+    }
+
+    // name -> (file index, kind, item index)
+    let mut definitions: HashMap<String, Vec<(usize, TypeKind, usize)>> = HashMap::new();
+    for (file_idx, (_, snapshot)) in files.iter().enumerate() {
+        for (item_idx, strct) in snapshot.structs.iter().enumerate() {
+            definitions.entry(strct.name.clone()).or_default().push((file_idx, TypeKind::Struct, item_idx));
+        }
+        for (item_idx, enm) in snapshot.enums.iter().enumerate() {
+            definitions.entry(enm.name.clone()).or_default().push((file_idx, TypeKind::Enum, item_idx));
+        }
+    }
+
+    let mut links = Vec::new();
+    for (file_idx, (scope, snapshot)) in files.iter().enumerate() {
+        for (impl_idx, imp) in snapshot.impls.iter().enumerate() {
+            let Some(candidates) = definitions.get(&imp.for_type) else { continue };
+            let dir = Path::new(&snapshot.path).parent();
+            let unique = |matches: Vec<&(usize, TypeKind, usize)>| match matches[..] {
+                [single] => Some(*single),
+                _ => None,
+            };
+
+            let target = unique(candidates.iter().filter(|(f, _, _)| *f == file_idx).collect())
+                .or_else(|| unique(candidates.iter().filter(|(f, _, _)| files[*f].0 == *scope && Path::new(&files[*f].1.path).parent() == dir).collect()))
+                .or_else(|| unique(candidates.iter().filter(|(f, _, _)| files[*f].0 == *scope).collect()))
+                .or_else(|| unique(candidates.iter().filter(|(f, _, _)| files[*f].0.0 == scope.0).collect()))
+                .or_else(|| unique(candidates.iter().collect()));
+            if let Some(target) = target {
+                links.push((file_idx, impl_idx, target));
+            }
+        }
+    }
+
+    for (file_idx, impl_idx, (def_file, kind, item_idx)) in links {
+        let (methods, impl_ref) = {
+            let snapshot = &files[file_idx].1;
+            let imp = &snapshot.impls[impl_idx];
+            let impl_ref = ImplRef {
+                trait_name: imp.trait_name.clone(),
+                location: Location { path: snapshot.path.clone(), span: imp.span },
+            };
+            (imp.methods.iter().map(|m| m.name.clone()).collect::<Vec<_>>(), impl_ref)
+        };
+
+        let def_snapshot = &mut files[def_file].1;
+        let def_path = def_snapshot.path.clone();
+        let def_span = match kind {
+            TypeKind::Struct => {
+                let strct = &mut def_snapshot.structs[item_idx];
+                strct.methods.extend(methods);
+                strct.impls.push(impl_ref);
+                strct.span
+            }
+            TypeKind::Enum => {
+                let enm = &mut def_snapshot.enums[item_idx];
+                enm.methods.extend(methods);
+                enm.impls.push(impl_ref);
+                enm.span
+            }
+        };
+        files[file_idx].1.impls[impl_idx].target = Some(Location { path: def_path, span: def_span });
+    }
+}
+
+/// Value of a `#[path = "..."]` attribute.
+fn path_attr_of(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(path), .. }) => Some(path.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn collect_files_mut<'a>(module: &'a mut ModuleNode, out: &mut Vec<&'a mut FileNode>) {
+    if let Some(file) = &mut module.file {
+        out.push(file);
+    }
+    for submodule in &mut module.submodules {
+        collect_files_mut(submodule, out);
+    }
+}
+
+fn collect_files<'a>(module: &'a ModuleNode, out: &mut Vec<&'a FileNode>) {
+    if let Some(file) = &module.file {
+        out.push(file);
+    }
+    for submodule in &module.submodules {
+        collect_files(submodule, out);
+    }
+}
+
+fn collect_snapshots_mut<'a>(module: &'a mut ModuleNode, out: &mut Vec<&'a mut RustFileSnapshot>) {
+    if let Some(snapshot) = module.file.as_mut().and_then(|file| file.snapshot.as_mut()) {
+        out.push(snapshot);
+    }
+    for submodule in &mut module.submodules {
+        collect_snapshots_mut(submodule, out);
     }
 }
@@ -0,0 +1,47 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A problem met while scanning a project. Scans keep going past the file or crate
+/// it concerns and collect these in `ProjectGraph::errors`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ScanError {
+    /// A source file could not be read.
+    Io { path: PathBuf, message: String },
+    /// A `Cargo.toml` could not be read or is not a valid manifest.
+    Manifest { path: PathBuf, message: String },
+    /// A source file is not valid Rust. `line` is 1-based and `column` 1-based in chars.
+    Parse { path: PathBuf, line: usize, column: usize, message: String },
+}
+
+impl ScanError {
+    pub fn io(path: &Path, err: &std::io::Error) -> Self {
+        ScanError::Io { path: path.to_path_buf(), message: err.to_string() }
+    }
+
+    pub fn manifest(path: &Path, message: impl ToString) -> Self {
+        ScanError::Manifest { path: path.to_path_buf(), message: message.to_string() }
+    }
+
+    pub fn parse(path: &Path, err: &syn::Error) -> Self {
+        let start = err.span().start();
+        ScanError::Parse { path: path.to_path_buf(), line: start.line, column: start.column + 1, message: err.to_string() }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            ScanError::Io { path, .. } | ScanError::Manifest { path, .. } | ScanError::Parse { path, .. } => path,
+        }
+    }
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Io { path, message } => write!(f, "{}: cannot read file: {}", path.display(), message),
+            ScanError::Manifest { path, message } => write!(f, "{}: invalid manifest: {}", path.display(), message),
+            ScanError::Parse { path, line, column, message } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
@@ -1,11 +1,19 @@
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use crate::types::RustFileSnapshot;
+use crate::calls::CallGraph;
+use crate::error::ScanError;
+use crate::symbols::{Resolution, Symbol, SymbolTable};
+use crate::type_graph::TypeGraph;
+use crate::types::{RustFileSnapshot, Span};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProjectGraph {
     pub root_path: PathBuf,
     pub crates: Vec<CrateNode>,
+    pub errors: Vec<ScanError>, // Files and manifests the scan had to skip, sorted by path
+    pub calls: CallGraph,
+    pub types: TypeGraph,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -13,14 +21,129 @@ pub struct CrateNode {
     pub name: String,
     pub version: String,
     pub path: PathBuf,
+    pub targets: Vec<TargetNode>, // lib, bins, examples, tests, benches, build script; never empty
+    pub orphans: Vec<FileNode>,   // Files under `src/` that no target's module tree reaches
+    pub dependencies: Vec<DependencyNode>, // Normal, dev and build dependencies, target-specific ones included
+    pub default_member: bool,     // Built by a bare `cargo build` in the workspace root
+}
+
+/// A dependency declared by a crate. Path dependencies on crates in the graph are
+/// edges to those crates; any other dependency is external.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyNode {
+    pub name: String,    // Name the crate uses for it: the key in `[dependencies]`
+    pub package: String, // Package name, differing from `name` for renamed dependencies
+    pub kind: DependencyKind,
+    pub requirement: Option<String>, // Version requirement; None for path or git deps without one
+    pub path: Option<PathBuf>,
+    pub optional: bool,
+    pub workspace_crate: Option<usize>, // Index of the depended-on crate in `ProjectGraph::crates`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+/// One compilation target of a crate, rooted at its own crate root file.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetNode {
+    pub name: String,
+    pub kind: TargetKind,
     pub root_module: ModuleNode,
+    pub symbols: SymbolTable, // Items of the module tree and resolved `use`s; built once files are parsed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+    BuildScript,
+}
+
+impl ProjectGraph {
+    /// Edges between crates of the graph: `(dependent, dependency, kind)` as indices
+    /// into `crates`.
+    pub fn crate_edges(&self) -> Vec<(usize, usize, DependencyKind)> {
+        self.crates.iter().enumerate()
+            .flat_map(|(idx, crate_node)| {
+                crate_node.dependencies.iter()
+                    .filter_map(move |dep| dep.workspace_crate.map(|target| (idx, target, dep.kind)))
+            })
+            .collect()
+    }
+
+    /// The item a path names in crate `crate_idx`, as `(crate index, symbol)`. The path
+    /// is relative to the crate root (`crate::engine::TraversalEngine`) and may go
+    /// through re-exports and into other crates of the graph the crate depends on.
+    pub fn find_symbol(&self, crate_idx: usize, path: &str) -> Option<(usize, &Symbol)> {
+        let (mut crate_idx, mut path) = (crate_idx, path.to_string());
+        // Every step enters another crate; more steps than crates means a re-export cycle
+        for _ in 0..=self.crates.len() {
+            let symbols = self.crates.get(crate_idx)?.symbols();
+            match symbols.resolve("crate", &path)? {
+                Resolution::Local(local) => return symbols.get(&local).map(|symbol| (crate_idx, symbol)),
+                Resolution::External(external) => {
+                    let (name, rest) = external.split_once("::")?;
+                    crate_idx = self.extern_crate(crate_idx, name)?;
+                    path = format!("crate::{}", rest);
+                }
+            }
+        }
+        None
+    }
+
+    /// Snapshots of the files the targets' module trees reach. Files reachable from
+    /// several targets are listed once, so their items have one location each.
+    pub(crate) fn reached_snapshots(&self) -> Vec<&RustFileSnapshot> {
+        let mut seen = HashSet::new();
+        let mut snapshots = Vec::new();
+        for target in self.crates.iter().flat_map(|crate_node| &crate_node.targets) {
+            collect_snapshots(&target.root_module, &mut seen, &mut snapshots);
+        }
+        snapshots
+    }
+
+    /// Crate of the graph that `name` refers to in crate `crate_idx`: a path dependency,
+    /// or the crate's own library as its binaries see it.
+    fn extern_crate(&self, crate_idx: usize, name: &str) -> Option<usize> {
+        let crate_node = &self.crates[crate_idx];
+        if crate_node.name.replace('-', "_") == name {
+            return Some(crate_idx);
+        }
+        crate_node.dependencies.iter()
+            .find(|dep| dep.name.replace('-', "_") == name)
+            .and_then(|dep| dep.workspace_crate)
+    }
+}
+
+impl CrateNode {
+    /// Root module of the library target, or of the first target for crates without one.
+    pub fn root_module(&self) -> &ModuleNode {
+        &self.primary_target().root_module
+    }
+
+    /// Symbol table of the same target as `root_module`.
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.primary_target().symbols
+    }
+
+    fn primary_target(&self) -> &TargetNode {
+        self.targets.iter().find(|target| target.kind == TargetKind::Lib).unwrap_or(&self.targets[0])
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ModuleNode {
     pub name: String,
-    pub path: Option<PathBuf>, // Directory path if it's a dir module
-    pub file: Option<FileNode>, // The file defining this module (mod.rs or name.rs)
+    pub path: Option<PathBuf>, // Directory where child modules are looked up
+    pub file: Option<FileNode>, // The file defining this module (mod.rs or name.rs); None for inline modules
+    pub docs: Option<String>, // Docs on the `mod` declaration followed by the file's `//!` docs
     pub submodules: Vec<ModuleNode>,
 }
 
@@ -29,4 +152,82 @@ pub struct FileNode {
     pub path: PathBuf,
     pub hash: String,
     pub snapshot: Option<RustFileSnapshot>, // Content, loaded lazily or cached
+    #[serde(skip)]
+    pub(crate) source: Option<FileSource>, // Read at discovery, until the file is parsed
+}
+
+/// What discovery read of a file that was not in the cache.
+#[derive(Debug, Clone)]
+pub(crate) struct FileSource {
+    pub content: String,
+    pub module_items: Option<String>, // As lexed by discovery; None for orphans, which it does not reach
+}
+
+/// Lookups over the nodes of a call or type graph, whose nodes are paths of targets,
+/// and over its edges once they are linked.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeIndex {
+    by_target: HashMap<(usize, usize), HashMap<String, usize>>, // (crate, target) -> path -> node
+    by_crate: Vec<HashMap<String, usize>>, // Per crate: path -> node of its primary target
+    outgoing: Vec<Vec<usize>>, // Per node: the edges from it
+    incoming: Vec<Vec<usize>>, // Per node: the edges to it
+}
+
+impl NodeIndex {
+    /// Indexes `(crate, target, path)` nodes, given by crate, then target.
+    pub fn new<'a>(crate_count: usize, nodes: impl Iterator<Item = (usize, usize, &'a str)>) -> Self {
+        let mut index = NodeIndex { by_crate: vec![HashMap::new(); crate_count], ..NodeIndex::default() };
+        for (idx, (crate_idx, target_idx, path)) in nodes.enumerate() {
+            index.by_target.entry((crate_idx, target_idx)).or_default().insert(path.to_string(), idx);
+            // Targets are sorted with the library first, which is what other crates see
+            index.by_crate[crate_idx].entry(path.to_string()).or_insert(idx);
+            index.outgoing.push(Vec::new());
+            index.incoming.push(Vec::new());
+        }
+        index
+    }
+
+    /// Indexes `(from, to)` edges, in order; `to` is None for edges leaving the graph.
+    pub fn link(&mut self, edges: impl Iterator<Item = (usize, Option<usize>)>) {
+        for (idx, (from, to)) in edges.enumerate() {
+            self.outgoing[from].push(idx);
+            if let Some(to) = to {
+                self.incoming[to].push(idx);
+            }
+        }
+    }
+
+    /// Node of `path` in one target.
+    pub fn get(&self, crate_idx: usize, target_idx: usize, path: &str) -> Option<usize> {
+        self.by_target.get(&(crate_idx, target_idx))?.get(path).copied()
+    }
+
+    /// Node of `path` in the crate's library target if it has one.
+    pub fn node(&self, crate_idx: usize, path: &str) -> Option<usize> {
+        self.by_crate.get(crate_idx)?.get(path).copied()
+    }
+
+    pub fn outgoing(&self, node: usize) -> &[usize] {
+        self.outgoing.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn incoming(&self, node: usize) -> &[usize] {
+        self.incoming.get(node).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Key of an item of a snapshot: its file and where it starts.
+pub(crate) fn location_key<'a>(path: &'a str, span: &Span) -> (&'a str, usize, usize) {
+    (path, span.start_line, span.start_column)
+}
+
+fn collect_snapshots<'a>(module: &'a ModuleNode, seen: &mut HashSet<&'a str>, out: &mut Vec<&'a RustFileSnapshot>) {
+    if let Some(snapshot) = module.file.as_ref().and_then(|file| file.snapshot.as_ref()) {
+        if seen.insert(snapshot.path.as_str()) {
+            out.push(snapshot);
+        }
+    }
+    for submodule in &module.submodules {
+        collect_snapshots(submodule, seen, out);
+    }
 }
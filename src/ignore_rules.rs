@@ -0,0 +1,74 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Files read for ignore patterns, relative to the scan root, in increasing priority.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".furnaceignore"];
+
+/// Paths the engine must not read, in gitignore syntax: globs, `/`-anchored and
+/// directory-only patterns, and `!` negations, where a later pattern beats an
+/// earlier one. Patterns come from the root's `.gitignore`, then `.furnaceignore`,
+/// then the `ignore` list in `.furnacerc.toml`.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    matcher: Gitignore,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for file in IGNORE_FILES {
+            let path = root.join(file);
+            if path.is_file() {
+                if let Some(err) = builder.add(&path) {
+                    eprintln!("Warning: Failed to read {}: {}", path.display(), err);
+                }
+            }
+        }
+        for pattern in patterns {
+            if let Err(err) = builder.add_line(None, pattern) {
+                eprintln!("Warning: Invalid ignore pattern '{}': {}", pattern, err);
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|_| Gitignore::empty());
+        Self { root: root.to_path_buf(), matcher }
+    }
+
+    /// Whether `path`, or a directory containing it, is ignored. Paths outside the
+    /// scan root never are.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else { return false };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        self.matcher.matched_path_or_any_parents(relative, is_dir).is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_syntax() {
+        let patterns = ["test", "/generated/", "*.g.rs", "!keep.g.rs"].map(String::from);
+        let rules = IgnoreRules::load(Path::new("project"), &patterns);
+        let ignored = |path: &str, is_dir| rules.is_ignored(&Path::new("project").join(path), is_dir);
+
+        // Whole path components only, unlike a substring match
+        assert!(ignored("src/test/mod.rs", false));
+        assert!(!ignored("src/contest.rs", false));
+
+        // Anchored, directory-only
+        assert!(ignored("generated/api.rs", false));
+        assert!(!ignored("src/generated/api.rs", false));
+        assert!(!ignored("generated", false));
+
+        // Globs and negation
+        assert!(ignored("src/parser.g.rs", false));
+        assert!(!ignored("src/keep.g.rs", false));
+
+        assert!(!ignored("", true));
+        assert!(!rules.is_ignored(Path::new("elsewhere/test/a.rs"), false));
+    }
+}
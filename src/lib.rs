@@ -3,6 +3,13 @@ pub mod linting;
 pub mod visitor;
 pub mod config;
 pub mod graph;
+pub mod symbols;
+pub mod calls;
+pub mod type_graph;
 pub mod engine;
+pub mod error;
+pub mod cfg;
+pub mod cache;
+pub mod ignore_rules;
 pub mod output;
 pub mod ai_linting;
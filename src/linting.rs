@@ -1,5 +1,6 @@
 use crate::types::RustFileSnapshot;
 use crate::config::LintConfig;
+use crate::graph::FileNode;
 
 pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Vec<String> {
     let mut warnings = Vec::new();
@@ -12,11 +13,11 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
     for snapshot in snapshots {
         // Complexity: Function argument count
         if let Some(max_args) = config.complexity.max_args {
-            for func in &snapshot.functions {
+            for func in snapshot.all_functions() {
                 if func.args.len() > max_args {
                     warnings.push(format!(
                         "Warning: Function '{}' in '{}' has {} arguments (max {} recommended)",
-                        func.name, snapshot.path, func.args.len(), max_args
+                        func.qualified_name(), func.span.location(&snapshot.path), func.args.len(), max_args
                     ));
                 }
             }
@@ -28,7 +29,7 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
                 if strct.fields.len() > max_fields {
                     warnings.push(format!(
                         "Warning: Struct '{}' in '{}' has {} fields (max {} recommended)",
-                        strct.name, snapshot.path, strct.fields.len(), max_fields
+                        strct.name, strct.span.location(&snapshot.path), strct.fields.len(), max_fields
                     ));
                 }
             }
@@ -36,11 +37,11 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
 
         // Naming: Function snake_case
         if config.naming.enforce_snake_case_functions == Some(true) {
-            for func in &snapshot.functions {
+            for func in snapshot.all_functions() {
                 if !is_snake_case(&func.name) {
                     warnings.push(format!(
                         "Warning: Function '{}' in '{}' should use snake_case",
-                        func.name, snapshot.path
+                        func.qualified_name(), func.span.location(&snapshot.path)
                     ));
                 }
             }
@@ -48,12 +49,12 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
 
         // Naming: Variable snake_case
         if config.naming.enforce_snake_case_variables == Some(true) {
-            for func in &snapshot.functions {
-                for (var_name, _var_type) in &func.variables {
-                    if !is_snake_case(var_name) {
+            for func in snapshot.all_functions() {
+                for var in &func.variables {
+                    if !is_snake_case(&var.name) {
                         warnings.push(format!(
                             "Warning: Variable '{}' in function '{}' ('{}') should use snake_case",
-                            var_name, func.name, snapshot.path
+                            var.name, func.qualified_name(), var.span.location(&snapshot.path)
                         ));
                     }
                 }
@@ -66,7 +67,7 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
                 if !is_pascal_case(&strct.name) {
                     warnings.push(format!(
                         "Warning: Struct '{}' in '{}' should use PascalCase",
-                        strct.name, snapshot.path
+                        strct.name, strct.span.location(&snapshot.path)
                     ));
                 }
             }
@@ -74,7 +75,51 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
                 if !is_pascal_case(&enm.name) {
                     warnings.push(format!(
                         "Warning: Enum '{}' in '{}' should use PascalCase",
-                        enm.name, snapshot.path
+                        enm.name, enm.span.location(&snapshot.path)
+                    ));
+                }
+            }
+            for trt in &snapshot.traits {
+                if !is_pascal_case(&trt.name) {
+                    warnings.push(format!(
+                        "Warning: Trait '{}' in '{}' should use PascalCase",
+                        trt.name, trt.span.location(&snapshot.path)
+                    ));
+                }
+            }
+            for union in &snapshot.unions {
+                if !is_pascal_case(&union.name) {
+                    warnings.push(format!(
+                        "Warning: Union '{}' in '{}' should use PascalCase",
+                        union.name, union.span.location(&snapshot.path)
+                    ));
+                }
+            }
+            for alias in &snapshot.type_aliases {
+                if !is_pascal_case(&alias.name) {
+                    warnings.push(format!(
+                        "Warning: Type alias '{}' in '{}' should use PascalCase",
+                        alias.name, alias.span.location(&snapshot.path)
+                    ));
+                }
+            }
+        }
+
+        // Naming: Constant SCREAMING_SNAKE_CASE
+        if config.naming.enforce_screaming_snake_case_constants == Some(true) {
+            for cnst in &snapshot.consts {
+                if cnst.name != "_" && !is_screaming_snake_case(&cnst.name) {
+                    warnings.push(format!(
+                        "Warning: Constant '{}' in '{}' should use SCREAMING_SNAKE_CASE",
+                        cnst.name, cnst.span.location(&snapshot.path)
+                    ));
+                }
+            }
+            for stat in &snapshot.statics {
+                if !is_screaming_snake_case(&stat.name) {
+                    warnings.push(format!(
+                        "Warning: Static '{}' in '{}' should use SCREAMING_SNAKE_CASE",
+                        stat.name, stat.span.location(&snapshot.path)
                     ));
                 }
             }
@@ -82,12 +127,12 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
 
         // Naming: Discouraged names
         if let Some(discouraged) = &config.naming.discouraged_names {
-            for func in &snapshot.functions {
-                for (var_name, _var_type) in &func.variables {
-                    if discouraged.contains(var_name) {
+            for func in snapshot.all_functions() {
+                for var in &func.variables {
+                    if discouraged.contains(&var.name) {
                         warnings.push(format!(
                             "Warning: Discouraged variable name '{}' in function '{}' ('{}')",
-                            var_name, func.name, snapshot.path
+                            var.name, func.qualified_name(), var.span.location(&snapshot.path)
                         ));
                     }
                 }
@@ -98,6 +143,19 @@ pub fn lint_snapshots(snapshots: &[RustFileSnapshot], config: &LintConfig) -> Ve
     warnings
 }
 
+/// Files no module tree reaches are either forgotten `mod` declarations or dead code.
+pub fn lint_orphans(orphans: &[FileNode], config: &LintConfig) -> Vec<String> {
+    if config.enabled == Some(false) || config.style.warn_orphan_files != Some(true) {
+        return Vec::new();
+    }
+    orphans.iter()
+        .map(|file| format!(
+            "Warning: File '{}' is not reachable from any module tree (missing `mod` declaration or dead code)",
+            file.path.display()
+        ))
+        .collect()
+}
+
 fn is_snake_case(s: &str) -> bool {
     // Allow leading underscore
     let s = s.trim_start_matches('_');
@@ -105,6 +163,11 @@ fn is_snake_case(s: &str) -> bool {
     s.chars().all(|c| c.is_lowercase() || c.is_numeric() || c == '_')
 }
 
+fn is_screaming_snake_case(s: &str) -> bool {
+    let s = s.trim_start_matches('_');
+    s.chars().all(|c| c.is_uppercase() || c.is_numeric() || c == '_')
+}
+
 fn is_pascal_case(s: &str) -> bool {
     if s.is_empty() {
         return false;
@@ -116,4 +179,57 @@ fn is_pascal_case(s: &str) -> bool {
     } else {
         false
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FunctionSnapshot, ImplSnapshot, Span, ConstSnapshot, StaticSnapshot, TypeAliasSnapshot};
+
+    #[test]
+    fn test_methods_are_linted() {
+        let method = FunctionSnapshot {
+            name: "doWork".to_string(),
+            args: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            owner: Some("Worker".to_string()),
+            span: Span { start_line: 7, ..Span::default() },
+            ..FunctionSnapshot::default()
+        };
+        let snapshot = RustFileSnapshot {
+            path: "src/worker.rs".to_string(),
+            impls: vec![ImplSnapshot { for_type: "Worker".to_string(), methods: vec![method], ..ImplSnapshot::default() }],
+            ..RustFileSnapshot::default()
+        };
+        let mut config = LintConfig::default();
+        config.complexity.max_args = Some(2);
+        config.naming.enforce_snake_case_functions = Some(true);
+
+        let warnings = lint_snapshots(&[snapshot], &config);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("'Worker::doWork' in 'src/worker.rs:7' has 3 arguments"));
+        assert!(warnings[1].contains("should use snake_case"));
+    }
+
+    #[test]
+    fn test_constant_and_type_naming() {
+        let snapshot = RustFileSnapshot {
+            path: "src/consts.rs".to_string(),
+            consts: vec![
+                ConstSnapshot { name: "MAX_SIZE".to_string(), ..ConstSnapshot::default() },
+                ConstSnapshot { name: "maxSize".to_string(), ..ConstSnapshot::default() },
+            ],
+            statics: vec![StaticSnapshot { name: "counter".to_string(), ..StaticSnapshot::default() }],
+            type_aliases: vec![TypeAliasSnapshot { name: "result".to_string(), ..TypeAliasSnapshot::default() }],
+            ..RustFileSnapshot::default()
+        };
+        let mut config = LintConfig::default();
+        config.naming.enforce_screaming_snake_case_constants = Some(true);
+        config.naming.enforce_pascal_case_types = Some(true);
+
+        let warnings = lint_snapshots(&[snapshot], &config);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("Type alias 'result'"));
+        assert!(warnings[1].contains("Constant 'maxSize'"));
+        assert!(warnings[2].contains("Static 'counter'"));
+    }
+}
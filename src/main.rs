@@ -1,12 +1,14 @@
 use furnace::types::RustFileSnapshot;
-use furnace::linting::lint_snapshots;
+use furnace::linting::{lint_snapshots, lint_orphans};
 use furnace::config::load_config;
-use furnace::engine::TraversalEngine;
+use furnace::engine::{TraversalEngine, ScanOptions, LayoutSource};
+use furnace::cfg::FeatureSelection;
 use furnace::graph::ModuleNode;
 use furnace::output::{OutputStyle, OutputRenderer, Layout, Detail, ColorMode, SymbolSet};
 
 use clap::{Parser, ValueEnum};
 use colored::*;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::fs;
 
@@ -79,6 +81,44 @@ struct Args {
     #[arg(long, value_enum)]
     symbols: Option<SymbolArg>,
 
+    // ===== SCAN SCOPE =====
+    /// Features to enable in the scanned crates (comma separated), on top of their defaults
+    #[arg(long, value_delimiter = ',', value_name = "FEATURES")]
+    features: Vec<String>,
+
+    /// Enable every feature of the scanned crates
+    #[arg(long)]
+    all_features: bool,
+
+    /// Do not enable the scanned crates' `default` features
+    #[arg(long)]
+    no_default_features: bool,
+
+    /// Evaluate target cfgs (`unix`, `target_os = ".."`) for this OS, e.g. linux or windows
+    #[arg(long, value_name = "OS")]
+    target_os: Option<String>,
+
+    /// Only scan the workspace's `default-members`
+    #[arg(long)]
+    default_members: bool,
+
+    /// Take packages and targets from `cargo metadata` instead of parsing manifests
+    #[arg(long)]
+    cargo_metadata: bool,
+
+    /// Take packages and targets from saved `cargo metadata --format-version 1` output
+    #[arg(long, value_name = "FILE")]
+    metadata_file: Option<PathBuf>,
+
+    /// Parse every file again instead of reusing `<target dir>/furnace-cache/`
+    #[arg(long)]
+    no_cache: bool,
+
+    // ===== GRAPH EXPORT =====
+    /// Write the type dependency graph to FILE: Graphviz DOT for `.dot` and `.gv`, JSON otherwise
+    #[arg(long, value_name = "FILE")]
+    type_graph: Option<PathBuf>,
+
     // ===== AI-POWERED ANALYSIS =====
     /// Enable AI-powered code analysis (requires --features ai and API key)
     #[arg(long)]
@@ -133,17 +173,15 @@ enum SymbolArg {
     Unicode,
 }
 
-fn collect_snapshots(module: &ModuleNode, snapshots: &mut Vec<RustFileSnapshot>, ignore: &[String]) {
+fn collect_snapshots(module: &ModuleNode, snapshots: &mut Vec<RustFileSnapshot>, seen: &mut HashSet<String>) {
     if let Some(file_node) = &module.file {
-        let path_str = file_node.path.to_string_lossy();
-        if !ignore.iter().any(|pattern| path_str.contains(pattern)) {
-            if let Some(snap) = &file_node.snapshot {
-                snapshots.push(snap.clone());
-            }
+        // Targets may share module files; report each file once
+        if let Some(snap) = file_node.snapshot.as_ref().filter(|snap| seen.insert(snap.path.clone())) {
+            snapshots.push(snap.clone());
         }
     }
     for submodule in &module.submodules {
-        collect_snapshots(submodule, snapshots, ignore);
+        collect_snapshots(submodule, snapshots, seen);
     }
 }
 
@@ -153,17 +191,64 @@ fn main() {
     let config = load_config(&project_path);
 
     // Use the new Semantic Traversal Engine
-    let engine = TraversalEngine::new(project_path.clone());
-    let graph = engine.scan();
+    let options = ScanOptions {
+        features: FeatureSelection {
+            features: args.features.clone(),
+            all_features: args.all_features,
+            no_default_features: args.no_default_features,
+        },
+        target_os: args.target_os.clone(),
+        default_members_only: args.default_members,
+        cache: !args.no_cache,
+        cache_dir: None,
+        ignore: config.ignore.clone(),
+        layout: match (&args.metadata_file, args.cargo_metadata) {
+            (Some(file), _) => LayoutSource::MetadataFile(file.clone()),
+            (None, true) => LayoutSource::CargoMetadata,
+            (None, false) => LayoutSource::Manifest,
+        },
+    };
+    let engine = TraversalEngine::with_options(project_path.clone(), options);
+    let graph = match engine.try_scan() {
+        Ok(graph) => graph,
+        Err(err) => {
+            eprintln!("{} {}", "Error:".red().bold(), err);
+            std::process::exit(1);
+        }
+    };
+    // Skipped files and crates go to stderr so `--format json` output stays valid
+    for err in &graph.errors {
+        eprintln!("{} {}", "Scan error:".red().bold(), err);
+    }
+
+    if let Some(path) = &args.type_graph {
+        let is_dot = path.extension().is_some_and(|ext| ext == "dot" || ext == "gv");
+        let contents = if is_dot {
+            graph.types.to_dot(&graph)
+        } else {
+            serde_json::to_string_pretty(&graph.types).unwrap()
+        };
+        if let Err(err) = fs::write(path, contents) {
+            eprintln!("{} cannot write {}: {}", "Error:".red().bold(), path.display(), err);
+        }
+    }
 
     // Flatten graph to snapshots for existing linting/output logic
     let mut snapshots: Vec<RustFileSnapshot> = vec![];
+    let mut seen = HashSet::new();
     for crate_node in &graph.crates {
-        collect_snapshots(&crate_node.root_module, &mut snapshots, &config.ignore);
+        for target in &crate_node.targets {
+            collect_snapshots(&target.root_module, &mut snapshots, &mut seen);
+        }
     }
     
     // Run linting
-    let warnings = lint_snapshots(&snapshots[..], &config.lints);
+    let mut warnings = lint_snapshots(&snapshots[..], &config.lints);
+    let orphans: Vec<_> = graph.crates.iter()
+        .flat_map(|crate_node| crate_node.orphans.iter())
+        .cloned()
+        .collect();
+    warnings.extend(lint_orphans(&orphans, &config.lints));
 
     // Run AI analysis if requested
     if args.ai_lint {
@@ -267,6 +352,7 @@ fn main() {
         
         #[cfg(not(feature = "ai"))]
         {
+            let _ = provider;
             eprintln!("{}", "AI features are not enabled. Rebuild with:".yellow());
             eprintln!("  cargo build --features ai");
         }
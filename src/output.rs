@@ -1,4 +1,4 @@
-use crate::types::{RustFileSnapshot, FunctionSnapshot, StructSnapshot, EnumSnapshot};
+use crate::types::{RustFileSnapshot, FunctionSnapshot, StructSnapshot, EnumSnapshot, FieldSnapshot, VariantSnapshot, StructKind, ImplRef, Span, Visibility, doc_summary};
 use colored::*;
 
 #[derive(Debug, Clone)]
@@ -156,25 +156,36 @@ impl OutputRenderer {
         for snapshot in snapshots {
             output.push_str(&self.format_path(&snapshot.path));
             output.push('\n');
+            output.push_str(&self.format_docs(snapshot.docs.as_deref(), "  "));
             
             if !snapshot.functions.is_empty() {
                 output.push_str(&self.format_section_header("Functions"));
                 for func in &snapshot.functions {
-                    output.push_str(&self.format_function(func));
+                    output.push_str(&self.format_function(func, &snapshot.path));
+                    output.push_str(&self.format_docs(func.docs.as_deref(), "      "));
                 }
             }
             
             if !snapshot.structs.is_empty() {
                 output.push_str(&self.format_section_header("Structs"));
                 for strct in &snapshot.structs {
-                    output.push_str(&self.format_struct(strct));
+                    output.push_str(&self.format_struct(strct, &snapshot.path));
+                    output.push_str(&self.format_docs(strct.docs.as_deref(), "      "));
                 }
             }
             
             if !snapshot.enums.is_empty() {
                 output.push_str(&self.format_section_header("Enums"));
                 for enm in &snapshot.enums {
-                    output.push_str(&self.format_enum(enm));
+                    output.push_str(&self.format_enum(enm, &snapshot.path));
+                    output.push_str(&self.format_docs(enm.docs.as_deref(), "      "));
+                }
+            }
+
+            for (section, items) in self.other_sections(snapshot) {
+                output.push_str(&self.format_section_header(section));
+                for (text, span) in items {
+                    output.push_str(&format!("    {} @ {}\n", text, self.format_location(&snapshot.path, &span)));
                 }
             }
             
@@ -194,25 +205,36 @@ impl OutputRenderer {
         
         for snapshot in snapshots {
             output.push_str(&format!("{} 📄 {}\n", tree_sym.0, self.format_path(&snapshot.path)));
+            output.push_str(&self.format_docs(snapshot.docs.as_deref(), &format!("{}  ", tree_sym.1)));
             
             if !snapshot.functions.is_empty() {
                 output.push_str(&format!("{}  🔧 Functions:\n", tree_sym.1));
                 for func in &snapshot.functions {
-                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_function_inline(func)));
+                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_function_inline(func, &snapshot.path)));
+                    output.push_str(&self.format_docs(func.docs.as_deref(), &format!("{}    ", tree_sym.1)));
                 }
             }
             
             if !snapshot.structs.is_empty() {
                 output.push_str(&format!("{}  🏗️ Structs:\n", tree_sym.1));
                 for strct in &snapshot.structs {
-                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_struct_inline(strct)));
+                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_struct_inline(strct, &snapshot.path)));
+                    output.push_str(&self.format_docs(strct.docs.as_deref(), &format!("{}    ", tree_sym.1)));
                 }
             }
             
             if !snapshot.enums.is_empty() {
                 output.push_str(&format!("{}  🧩 Enums:\n", tree_sym.1));
                 for enm in &snapshot.enums {
-                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_enum_inline(enm)));
+                    output.push_str(&format!("{}  - {}\n", tree_sym.1, self.format_enum_inline(enm, &snapshot.path)));
+                    output.push_str(&self.format_docs(enm.docs.as_deref(), &format!("{}    ", tree_sym.1)));
+                }
+            }
+
+            for (section, items) in self.other_sections(snapshot) {
+                output.push_str(&format!("{}  {} {}:\n", tree_sym.1, section_icon(section), section));
+                for (text, span) in items {
+                    output.push_str(&format!("{}  - {} @ {}\n", tree_sym.1, text, self.format_location(&snapshot.path, &span)));
                 }
             }
         }
@@ -228,7 +250,7 @@ impl OutputRenderer {
         output.push_str("+----------------------+----------+----------+----------+\n");
         
         for snapshot in snapshots {
-            let path = snapshot.path.split('/').last().unwrap_or(&snapshot.path);
+            let path = snapshot.path.rsplit('/').next().unwrap_or(&snapshot.path);
             output.push_str(&format!(
                 "| {:<20} | {:<8} | {:<8} | {:<8} |\n",
                 self.truncate(path, 20),
@@ -239,6 +261,22 @@ impl OutputRenderer {
         }
         
         output.push_str("+----------------------+----------+----------+----------+\n");
+
+        // Item locations are never truncated so they stay clickable
+        output.push_str("\n+----------------------+----------+\n");
+        output.push_str("| Item                 | Kind     | Location\n");
+        output.push_str("+----------------------+----------+\n");
+        for snapshot in snapshots {
+            for (kind, name, span) in self.item_locations(snapshot) {
+                output.push_str(&format!(
+                    "| {:<20} | {:<8} | {}\n",
+                    self.truncate(name, 20),
+                    kind,
+                    span.location(&snapshot.path)
+                ));
+            }
+        }
+        output.push_str("+----------------------+----------+\n");
         output
     }
 
@@ -246,7 +284,7 @@ impl OutputRenderer {
         let mut output = String::new();
         
         for snapshot in snapshots {
-            let path = snapshot.path.split('/').last().unwrap_or(&snapshot.path);
+            let path = snapshot.path.rsplit('/').next().unwrap_or(&snapshot.path);
             output.push_str(&format!(
                 "{}: f={} s={} e={}\n",
                 path,
@@ -254,11 +292,99 @@ impl OutputRenderer {
                 snapshot.structs.len(),
                 snapshot.enums.len()
             ));
+            for (kind, name, span) in self.item_locations(snapshot) {
+                output.push_str(&format!(" {} {} {}\n", kind, name, span.location(&snapshot.path)));
+            }
         }
         
         output
     }
 
+    fn item_locations<'a>(&self, snapshot: &'a RustFileSnapshot) -> Vec<(&'static str, &'a str, Span)> {
+        let mut items = Vec::new();
+        items.extend(snapshot.functions.iter().map(|f| ("fn", f.name.as_str(), f.span)));
+        items.extend(snapshot.structs.iter().map(|s| ("struct", s.name.as_str(), s.span)));
+        items.extend(snapshot.enums.iter().map(|e| ("enum", e.name.as_str(), e.span)));
+        items.extend(snapshot.traits.iter().map(|t| ("trait", t.name.as_str(), t.span)));
+        items.extend(snapshot.unions.iter().map(|u| ("union", u.name.as_str(), u.span)));
+        items.extend(snapshot.type_aliases.iter().map(|t| ("type", t.name.as_str(), t.span)));
+        items.extend(snapshot.consts.iter().map(|c| ("const", c.name.as_str(), c.span)));
+        items.extend(snapshot.statics.iter().map(|s| ("static", s.name.as_str(), s.span)));
+        items.extend(snapshot.macros.iter().map(|m| ("macro", m.name.as_str(), m.span)));
+        items
+    }
+
+    /// Sections for the item kinds beyond functions, structs and enums (impls included), each entry
+    /// already formatted for the current detail level. Empty sections are omitted,
+    /// and `use` declarations only appear at verbose detail.
+    fn other_sections(&self, snapshot: &RustFileSnapshot) -> Vec<(&'static str, Vec<(String, Span)>)> {
+        let minimal = self.style.detail == Detail::Minimal;
+        let typed = |name: &str, ty: &str| if minimal { name.to_string() } else { format!("{}: {}", name, ty) };
+
+        let mut sections = vec![
+            ("Traits", snapshot.traits.iter().map(|t| {
+                let text = if minimal { t.name.clone() } else { format!("{} (methods: {})", t.name, t.methods.len()) };
+                (text, t.span)
+            }).collect::<Vec<_>>()),
+            ("Impls", snapshot.impls.iter().map(|i| {
+                let text = match self.style.detail {
+                    Detail::Minimal => i.for_type.clone(),
+                    Detail::Standard => format!("{} (methods: {})", i.header(), i.methods.len()),
+                    Detail::Verbose => format!(
+                        "{}: methods [{}]",
+                        i.header(),
+                        i.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ")
+                    ),
+                };
+                (text, i.span)
+            }).collect()),
+            ("Unions", snapshot.unions.iter().map(|u| {
+                let text = if minimal { u.name.clone() } else { format!("{}: fields [{}]", u.name, self.format_fields(&u.fields)) };
+                (text, u.span)
+            }).collect()),
+            ("Type Aliases", snapshot.type_aliases.iter().map(|t| {
+                let name = if t.generics.is_empty() { t.name.clone() } else { format!("{}<{}>", t.name, t.generics.join(", ")) };
+                (if minimal { t.name.clone() } else { format!("{} = {}", name, t.target) }, t.span)
+            }).collect()),
+            ("Constants", snapshot.consts.iter().map(|c| (typed(&c.name, &c.ty), c.span)).collect()),
+            ("Statics", snapshot.statics.iter().map(|s| {
+                let name = if s.mutable && !minimal { format!("mut {}", s.name) } else { s.name.clone() };
+                (typed(&name, &s.ty), s.span)
+            }).collect()),
+            ("Macros", snapshot.macros.iter().map(|m| (format!("{}!", m.name), m.span)).collect()),
+            ("Extern Blocks", snapshot.extern_blocks.iter().map(|b| {
+                let abi = b.abi.as_deref().unwrap_or("C");
+                let text = match self.style.detail {
+                    Detail::Minimal => format!("extern \"{}\"", abi),
+                    Detail::Standard => format!("extern \"{}\" (functions: {}, statics: {})", abi, b.functions.len(), b.statics.len()),
+                    Detail::Verbose => format!(
+                        "extern \"{}\" [{}]",
+                        abi,
+                        b.functions.iter().map(|f| f.signature_string())
+                            .chain(b.statics.iter().map(|s| format!("static {}: {}", s.name, s.ty)))
+                            .collect::<Vec<_>>().join("; ")
+                    ),
+                };
+                (text, b.span)
+            }).collect()),
+        ];
+        if self.style.detail == Detail::Verbose {
+            sections.push(("Uses", snapshot.uses.iter().map(|u| {
+                let mut text = u.path.clone();
+                if u.is_glob {
+                    text.push_str("::*");
+                }
+                if let Some(alias) = &u.alias {
+                    text.push_str(&format!(" as {}", alias));
+                }
+                (text, u.span)
+            }).collect()));
+        }
+
+        sections.retain(|(_, items)| !items.is_empty());
+        sections
+    }
+
     fn format_path(&self, path: &str) -> String {
         match self.style.color {
             ColorMode::Standard => path.bright_blue().to_string(),
@@ -270,97 +396,174 @@ impl OutputRenderer {
     fn format_section_header(&self, name: &str) -> String {
         match self.style.color {
             ColorMode::Standard => format!("  {}:\n", name.yellow().bold()),
-            ColorMode::Badges => {
-                let icon = match name {
-                    "Functions" => "🔧",
-                    "Structs" => "🏗️",
-                    "Enums" => "🧩",
-                    _ => "📦",
-                };
-                format!("  {} {}:\n", icon, name)
-            }
+            ColorMode::Badges => format!("  {} {}:\n", section_icon(name), name),
             ColorMode::None => format!("  {}:\n", name),
         }
     }
 
-    fn format_function(&self, func: &FunctionSnapshot) -> String {
+    fn format_function(&self, func: &FunctionSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &func.span);
         match self.style.detail {
-            Detail::Minimal => format!("    {}\n", func.name),
-            Detail::Standard => format!("    {} (args: {})\n", func.name, func.args.len()),
+            Detail::Minimal => format!("    {} @ {}\n", func.name, location),
+            Detail::Standard => format!("    {} (args: {}) @ {}\n", func.name, func.args.len(), location),
             Detail::Verbose => format!(
-                "    {} (args: {}, vars: {})\n",
-                func.name,
+                "    {}{} (args: {}, vars: {}) @ {}\n",
+                visibility_prefix(&func.visibility),
+                func.signature_string(),
                 func.args.len(),
-                func.variables.len()
+                func.variables.len(),
+                location
             ),
         }
     }
 
-    fn format_function_inline(&self, func: &FunctionSnapshot) -> String {
+    fn format_function_inline(&self, func: &FunctionSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &func.span);
         match self.style.detail {
-            Detail::Minimal => func.name.clone(),
-            Detail::Standard => format!("{}: args [{}]", func.name, func.args.join(", ")),
+            Detail::Minimal => format!("{} @ {}", func.name, location),
+            Detail::Standard => format!("{}: args [{}] @ {}", func.name, func.args.join(", "), location),
             Detail::Verbose => format!(
-                "{}: args [{}], variables [{}]",
-                func.name,
-                func.args.join(", "),
-                func.variables.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>().join(", ")
+                "{}{}: variables [{}] @ {}",
+                visibility_prefix(&func.visibility),
+                func.signature_string(),
+                func.variables.iter().map(|v| format!("{}{}:{}", if v.mutable { "mut " } else { "" }, v.name, v.span.start_line)).collect::<Vec<_>>().join(", "),
+                location
             ),
         }
     }
 
-    fn format_struct(&self, strct: &StructSnapshot) -> String {
+    fn format_struct(&self, strct: &StructSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &strct.span);
         match self.style.detail {
-            Detail::Minimal => format!("    {}\n", strct.name),
-            Detail::Standard => format!("    {} (fields: {})\n", strct.name, strct.fields.len()),
+            Detail::Minimal => format!("    {} @ {}\n", strct.name, location),
+            Detail::Standard => format!("    {} (fields: {}) @ {}\n", strct.name, strct.fields.len(), location),
             Detail::Verbose => format!(
-                "    {} (fields: {}, methods: {})\n",
+                "    {} (fields: {}, methods: {}){} @ {}\n",
                 strct.name,
                 strct.fields.len(),
-                strct.methods.len()
+                strct.methods.len(),
+                format_derives(&strct.derives),
+                location
             ),
         }
     }
 
-    fn format_struct_inline(&self, strct: &StructSnapshot) -> String {
+    fn format_struct_inline(&self, strct: &StructSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &strct.span);
         match self.style.detail {
-            Detail::Minimal => strct.name.clone(),
-            Detail::Standard => format!("{}: fields [{}]", strct.name, strct.fields.join(", ")),
+            Detail::Minimal => format!("{} @ {}", strct.name, location),
+            Detail::Standard => format!("{}: fields [{}] @ {}", strct.name, self.format_fields(&strct.fields), location),
             Detail::Verbose => format!(
-                "{}: fields [{}], methods [{}]",
+                "{}: fields [{}], methods [{}]{}{} @ {}",
                 strct.name,
-                strct.fields.join(", "),
-                strct.methods.join(", ")
+                self.format_fields(&strct.fields),
+                strct.methods.join(", "),
+                format_derives(&strct.derives),
+                self.format_impl_refs(&strct.impls),
+                location
             ),
         }
     }
 
-    fn format_enum(&self, enm: &EnumSnapshot) -> String {
+    fn format_enum(&self, enm: &EnumSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &enm.span);
         match self.style.detail {
-            Detail::Minimal => format!("    {}\n", enm.name),
-            Detail::Standard => format!("    {} (variants: {})\n", enm.name, enm.variants.len()),
+            Detail::Minimal => format!("    {} @ {}\n", enm.name, location),
+            Detail::Standard => format!("    {} (variants: {}) @ {}\n", enm.name, enm.variants.len(), location),
             Detail::Verbose => format!(
-                "    {} (variants: {}, methods: {})\n",
+                "    {} (variants: {}, methods: {}){} @ {}\n",
                 enm.name,
                 enm.variants.len(),
-                enm.methods.len()
+                enm.methods.len(),
+                format_derives(&enm.derives),
+                location
             ),
         }
     }
 
-    fn format_enum_inline(&self, enm: &EnumSnapshot) -> String {
+    fn format_enum_inline(&self, enm: &EnumSnapshot, path: &str) -> String {
+        let location = self.format_location(path, &enm.span);
         match self.style.detail {
-            Detail::Minimal => enm.name.clone(),
-            Detail::Standard => format!("{}: variants [{}]", enm.name, enm.variants.join(", ")),
+            Detail::Minimal => format!("{} @ {}", enm.name, location),
+            Detail::Standard => format!("{}: variants [{}] @ {}", enm.name, self.format_variants(&enm.variants), location),
             Detail::Verbose => format!(
-                "{}: variants [{}], methods [{}]",
+                "{}: variants [{}], methods [{}]{}{} @ {}",
                 enm.name,
-                enm.variants.join(", "),
-                enm.methods.join(", ")
+                self.format_variants(&enm.variants),
+                enm.methods.join(", "),
+                format_derives(&enm.derives),
+                self.format_impl_refs(&enm.impls),
+                location
             ),
         }
     }
 
+    /// Named fields show their name (plus type at verbose detail); tuple fields show their type.
+    fn format_fields(&self, fields: &[FieldSnapshot]) -> String {
+        fields.iter().map(|field| {
+            if field.is_positional() {
+                field.ty.clone()
+            } else if self.style.detail == Detail::Verbose {
+                format!("{}: {}", field.name, field.ty)
+            } else {
+                field.name.clone()
+            }
+        }).collect::<Vec<_>>().join(", ")
+    }
+
+    fn format_variants(&self, variants: &[VariantSnapshot]) -> String {
+        variants.iter().map(|variant| {
+            let mut text = variant.name.clone();
+            match variant.kind {
+                StructKind::Tuple => text.push_str(&format!("({})", self.format_fields(&variant.fields))),
+                StructKind::Named => text.push_str(&format!(" {{ {} }}", self.format_fields(&variant.fields))),
+                StructKind::Unit => {}
+            }
+            if let Some(discriminant) = &variant.discriminant {
+                text.push_str(&format!(" = {}", discriminant));
+            }
+            text
+        }).collect::<Vec<_>>().join(", ")
+    }
+
+    /// `, impls [Display @ src/a.rs:3, src/b.rs:9]`, or nothing when the type has no impls.
+    fn format_impl_refs(&self, impls: &[ImplRef]) -> String {
+        if impls.is_empty() {
+            return String::new();
+        }
+        let refs: Vec<String> = impls.iter().map(|imp| {
+            let location = self.format_location(&imp.location.path, &imp.location.span);
+            match &imp.trait_name {
+                Some(trait_name) => format!("{} @ {}", trait_name, location),
+                None => location,
+            }
+        }).collect();
+        format!(", impls [{}]", refs.join(", "))
+    }
+
+    /// `/// summary` line under an item at verbose detail; empty otherwise.
+    fn format_docs(&self, docs: Option<&str>, indent: &str) -> String {
+        if self.style.detail != Detail::Verbose {
+            return String::new();
+        }
+        let Some(summary) = doc_summary(docs) else {
+            return String::new();
+        };
+        let line = format!("/// {}", summary);
+        match self.style.color {
+            ColorMode::Standard => format!("{}{}\n", indent, line.green()),
+            _ => format!("{}{}\n", indent, line),
+        }
+    }
+
+    fn format_location(&self, path: &str, span: &Span) -> String {
+        let location = span.location(path);
+        match self.style.color {
+            ColorMode::Standard => location.dimmed().to_string(),
+            _ => location,
+        }
+    }
+
     fn truncate(&self, s: &str, max_len: usize) -> String {
         if s.len() <= max_len {
             s.to_string()
@@ -369,3 +572,38 @@ impl OutputRenderer {
         }
     }
 }
+
+fn section_icon(name: &str) -> &'static str {
+    match name {
+        "Functions" => "🔧",
+        "Structs" => "🏗️",
+        "Enums" => "🧩",
+        "Traits" => "🧬",
+        "Impls" => "🔗",
+        "Unions" => "🔀",
+        "Type Aliases" => "🏷️",
+        "Constants" => "🔒",
+        "Statics" => "📌",
+        "Macros" => "🪄",
+        "Extern Blocks" => "🔌",
+        "Uses" => "📥",
+        _ => "📦",
+    }
+}
+
+/// `pub ` / `pub(crate) ` ahead of a signature; empty for private items.
+fn visibility_prefix(visibility: &Visibility) -> String {
+    match visibility {
+        Visibility::Inherited => String::new(),
+        vis => format!("{} ", vis),
+    }
+}
+
+/// `, derives [Debug, Clone]`, or nothing when the type derives nothing.
+fn format_derives(derives: &[String]) -> String {
+    if derives.is_empty() {
+        String::new()
+    } else {
+        format!(", derives [{}]", derives.join(", "))
+    }
+}
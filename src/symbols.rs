@@ -0,0 +1,392 @@
+use crate::graph::ModuleNode;
+use crate::types::{has_attribute, ImplSnapshot, Location, RustFileSnapshot, Span, Visibility};
+use serde::Serialize;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SymbolKind {
+    Module,
+    Struct,
+    Enum,
+    Variant,
+    Union,
+    Trait,
+    TypeAlias,
+    Function,
+    Method, // Trait methods, and methods of impls whose self type is in the table
+    Const,
+    Static,
+    Macro,
+}
+
+/// An item defined in the crate, under its fully-qualified path.
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub path: String,   // `crate::engine::TraversalEngine`, `crate::graph::TargetKind::Lib`
+    pub kind: SymbolKind,
+    pub module: String, // Module the item is declared in, which its visibility is relative to
+    pub visibility: Visibility,
+    pub location: Location,
+}
+
+/// What a path names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Resolution {
+    Local(String),    // Fully-qualified path of a symbol of the table, or `crate`
+    External(String), // Path into another crate: `std::collections::HashMap`
+}
+
+/// One leaf of a `use` declaration, placed in its module.
+#[derive(Debug, Clone, Serialize)]
+pub struct Import {
+    pub module: String,
+    pub path: String,         // As written: `super::graph::ModuleNode`
+    pub name: Option<String>, // Name bound in `module`; None for globs and `as _`
+    pub is_glob: bool,
+    pub visibility: Visibility,
+    pub location: Location,
+    pub resolution: Option<Resolution>, // None when the path names nothing the table knows
+}
+
+/// An impl block whose self type is in the table.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImplBlock {
+    pub self_type: String, // Fully-qualified path of the type
+    pub module: String,    // Module holding the block, which its paths resolve in
+    pub location: Location,
+}
+
+/// A name brought into a module by a `use`.
+#[derive(Debug, Clone, Serialize)]
+struct Binding {
+    resolution: Resolution,
+    visibility: Visibility,
+}
+
+/// Items of one crate (one target, to rustc) keyed by fully-qualified path, and the
+/// names its `use` declarations bring into each module.
+///
+/// Built from the snapshots of a module tree. Glob imports and re-exports are
+/// resolved to a fixpoint, so `pub use` chains and `use super::*` see each other
+/// whatever the order of files. A path whose first segment is neither `crate`,
+/// `self`, `super` nor a name in scope is taken to start with an extern crate.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SymbolTable {
+    symbols: BTreeMap<String, Symbol>,
+    imports: Vec<Import>,
+    impls: Vec<ImplBlock>, // Inherent impls first, then trait impls
+    scopes: BTreeMap<String, BTreeMap<String, Binding>>, // Module -> names bound by `use`
+}
+
+impl SymbolTable {
+    /// Builds the table of the module tree rooted at `root`, whose files must already
+    /// be parsed.
+    pub fn build(root: &ModuleNode) -> Self {
+        let mut table = SymbolTable::default();
+        let mut impls = Vec::new();
+        table.collect(root, "crate", &mut impls);
+        table.resolve_imports();
+        table.add_methods(&impls);
+        table
+    }
+
+    /// The item defined at `path`, without following re-exports.
+    pub fn get(&self, path: &str) -> Option<&Symbol> {
+        self.symbols.get(path)
+    }
+
+    /// All items, sorted by path.
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.values()
+    }
+
+    /// `use` declarations in file order, with what they resolved to.
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    /// Impl blocks of the types of the table.
+    pub fn impls(&self) -> &[ImplBlock] {
+        &self.impls
+    }
+
+    /// Resolves `path` as written in `module`, e.g. `resolve("crate::engine", "super::graph::ModuleNode")`.
+    pub fn resolve(&self, module: &str, path: &str) -> Option<Resolution> {
+        self.resolve_path(module, path, true)
+    }
+
+    /// The item a `crate::` path names, following re-exports: `crate::TraversalEngine`
+    /// for a `pub use engine::TraversalEngine;` in the crate root.
+    pub fn find(&self, path: &str) -> Option<&Symbol> {
+        match self.resolve(parent_of(path).unwrap_or("crate"), path)? {
+            Resolution::Local(path) => self.symbols.get(&path),
+            Resolution::External(_) => None,
+        }
+    }
+
+    /// Whether `path` is a struct, enum, union or type alias of the table.
+    pub fn is_type(&self, path: &str) -> bool {
+        matches!(
+            self.symbols.get(path).map(|symbol| symbol.kind),
+            Some(SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Union | SymbolKind::TypeAlias)
+        )
+    }
+
+    fn collect<'a>(&mut self, module: &'a ModuleNode, path: &str, impls: &mut Vec<(String, &'a ImplSnapshot, &'a str)>) {
+        if let Some(snapshot) = module.file.as_ref().and_then(|file| file.snapshot.as_ref()) {
+            self.collect_file(snapshot, path, impls);
+        }
+        // Inline modules are children too: their items are in the parent's file, but
+        // their `mod foo;` declarations have files of their own
+        for submodule in &module.submodules {
+            self.collect(submodule, &format!("{}::{}", path, submodule.name), impls);
+        }
+    }
+
+    fn collect_file<'a>(&mut self, snapshot: &'a RustFileSnapshot, base: &str, impls: &mut Vec<(String, &'a ImplSnapshot, &'a str)>) {
+        // Items of inline modules are listed flat with the file's own; their spans tell
+        // which module they are in
+        let inline: Vec<_> = snapshot.modules.iter().filter(|module| module.inline).collect();
+        let module_of = |span: &Span| {
+            let mut enclosing: Vec<_> = inline.iter().filter(|module| module.span != *span && encloses(&module.span, span)).collect();
+            enclosing.sort_by_key(|module| (module.span.start_line, module.span.start_column));
+            enclosing.iter().fold(base.to_string(), |path, module| format!("{}::{}", path, module.name))
+        };
+        let location = |span: Span| Location { path: snapshot.path.clone(), span };
+
+        for module in &snapshot.modules {
+            let scope = module_of(&module.span);
+            self.define(&scope, &module.name, SymbolKind::Module, &scope, module.visibility.clone(), location(module.span));
+        }
+        for strct in &snapshot.structs {
+            let scope = module_of(&strct.span);
+            self.define(&scope, &strct.name, SymbolKind::Struct, &scope, strct.visibility.clone(), location(strct.span));
+        }
+        for enm in &snapshot.enums {
+            let scope = module_of(&enm.span);
+            self.define(&scope, &enm.name, SymbolKind::Enum, &scope, enm.visibility.clone(), location(enm.span));
+            let enum_path = format!("{}::{}", scope, enm.name);
+            for variant in &enm.variants {
+                self.define(&enum_path, &variant.name, SymbolKind::Variant, &scope, Visibility::Public, location(variant.span));
+            }
+        }
+        for union in &snapshot.unions {
+            let scope = module_of(&union.span);
+            self.define(&scope, &union.name, SymbolKind::Union, &scope, union.visibility.clone(), location(union.span));
+        }
+        for tr in &snapshot.traits {
+            let scope = module_of(&tr.span);
+            self.define(&scope, &tr.name, SymbolKind::Trait, &scope, tr.visibility.clone(), location(tr.span));
+            let trait_path = format!("{}::{}", scope, tr.name);
+            for method in &tr.methods {
+                self.define(&trait_path, &method.name, SymbolKind::Method, &scope, Visibility::Public, location(method.span));
+            }
+        }
+        for alias in &snapshot.type_aliases {
+            let scope = module_of(&alias.span);
+            self.define(&scope, &alias.name, SymbolKind::TypeAlias, &scope, alias.visibility.clone(), location(alias.span));
+        }
+        for func in &snapshot.functions {
+            let scope = module_of(&func.span);
+            self.define(&scope, &func.name, SymbolKind::Function, &scope, func.visibility.clone(), location(func.span));
+        }
+        for constant in &snapshot.consts {
+            let scope = module_of(&constant.span);
+            self.define(&scope, &constant.name, SymbolKind::Const, &scope, constant.visibility.clone(), location(constant.span));
+        }
+        for stat in &snapshot.statics {
+            let scope = module_of(&stat.span);
+            self.define(&scope, &stat.name, SymbolKind::Static, &scope, stat.visibility.clone(), location(stat.span));
+        }
+        for block in &snapshot.extern_blocks {
+            let scope = module_of(&block.span);
+            for func in &block.functions {
+                self.define(&scope, &func.name, SymbolKind::Function, &scope, func.visibility.clone(), location(func.span));
+            }
+            for stat in &block.statics {
+                self.define(&scope, &stat.name, SymbolKind::Static, &scope, stat.visibility.clone(), location(stat.span));
+            }
+        }
+        // `#[macro_export]` puts a `macro_rules!` at the crate root, whatever module defines it
+        for mac in &snapshot.macros {
+            let (scope, visibility) = match has_attribute(&mac.attributes, "macro_export") {
+                true => ("crate".to_string(), Visibility::Public),
+                false => (module_of(&mac.span), Visibility::Inherited),
+            };
+            self.define(&scope, &mac.name, SymbolKind::Macro, &scope, visibility, location(mac.span));
+        }
+
+        for use_item in &snapshot.uses {
+            let name = match (&use_item.alias, use_item.is_glob) {
+                (_, true) => None,
+                (Some(alias), _) => Some(alias.clone()).filter(|alias| alias != "_"),
+                (None, _) => use_item.path.rsplit("::").next().map(String::from),
+            };
+            self.imports.push(Import {
+                module: module_of(&use_item.span),
+                path: use_item.path.clone(),
+                name,
+                is_glob: use_item.is_glob,
+                visibility: use_item.visibility.clone(),
+                location: location(use_item.span),
+                resolution: None,
+            });
+        }
+        for imp in &snapshot.impls {
+            impls.push((module_of(&imp.span), imp, snapshot.path.as_str()));
+        }
+    }
+
+    /// Adds `scope::name` declared in `module`. The first definition of a path wins.
+    fn define(&mut self, scope: &str, name: &str, kind: SymbolKind, module: &str, visibility: Visibility, location: Location) {
+        let path = format!("{}::{}", scope, name);
+        self.symbols.entry(path.clone()).or_insert(Symbol { path, kind, module: module.to_string(), visibility, location });
+    }
+
+    fn resolve_imports(&mut self) {
+        // Falling back to extern crates only once nothing else resolves, so a name a
+        // later glob brings into scope is not mistaken for one
+        for fallback in [false, true] {
+            loop {
+                let mut changed = false;
+                for idx in 0..self.imports.len() {
+                    let Import { module, path, name, visibility, .. } = self.imports[idx].clone();
+                    if self.imports[idx].resolution.is_none() {
+                        let Some(resolution) = self.resolve_path(&module, &path, fallback) else { continue };
+                        if let Some(name) = name {
+                            let binding = Binding { resolution: resolution.clone(), visibility: visibility.clone() };
+                            self.scopes.entry(module.clone()).or_default().insert(name, binding);
+                        }
+                        self.imports[idx].resolution = Some(resolution);
+                        changed = true;
+                    }
+                    if let Some(Resolution::Local(target)) = &self.imports[idx].resolution {
+                        if self.imports[idx].is_glob {
+                            changed |= self.expand_glob(&module, &target.clone(), &visibility);
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Binds in `module` the names of `target` visible from it that `module` does not
+    /// define or import by name already. Returns whether any were new.
+    fn expand_glob(&mut self, module: &str, target: &str, visibility: &Visibility) -> bool {
+        let is_namespace = target == "crate" || matches!(self.symbols.get(target).map(|symbol| symbol.kind), Some(SymbolKind::Module | SymbolKind::Enum));
+        if !is_namespace {
+            return false;
+        }
+
+        let prefix = format!("{}::", target);
+        let defined = self.symbols.range(prefix.clone()..)
+            .take_while(|(path, _)| path.starts_with(&prefix))
+            .filter(|(path, symbol)| !path[prefix.len()..].contains("::") && visible(&symbol.visibility, &symbol.module, module))
+            .map(|(path, _)| (path[prefix.len()..].to_string(), Resolution::Local(path.clone())));
+        let imported = self.scopes.get(target).into_iter().flatten()
+            .filter(|(_, binding)| visible(&binding.visibility, target, module))
+            .map(|(name, binding)| (name.clone(), binding.resolution.clone()));
+        let names: Vec<(String, Resolution)> = defined.chain(imported).collect();
+
+        let mut changed = false;
+        for (name, resolution) in names {
+            if self.symbols.contains_key(&format!("{}::{}", module, name)) {
+                continue;
+            }
+            if let Entry::Vacant(entry) = self.scopes.entry(module.to_string()).or_default().entry(name) {
+                entry.insert(Binding { resolution, visibility: visibility.clone() });
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Records the impls whose self type resolves to a type of the table and registers
+    /// their methods under the type's path. Inherent methods go first, so they shadow
+    /// trait methods.
+    fn add_methods(&mut self, impls: &[(String, &ImplSnapshot, &str)]) {
+        for inherent in [true, false] {
+            for (module, imp, file) in impls.iter().filter(|(_, imp, _)| imp.trait_name.is_none() == inherent) {
+                let Some(self_path) = &imp.self_path else { continue };
+                let Some(Resolution::Local(owner)) = self.resolve_path(module, self_path, false) else { continue };
+                if !self.is_type(&owner) {
+                    continue;
+                }
+                let location = Location { path: file.to_string(), span: imp.span };
+                self.impls.push(ImplBlock { self_type: owner.clone(), module: module.clone(), location });
+                for method in &imp.methods {
+                    // Methods of trait impls are as visible as the trait
+                    let visibility = if inherent { method.visibility.clone() } else { Visibility::Public };
+                    let location = Location { path: file.to_string(), span: method.span };
+                    self.define(&owner, &method.name, SymbolKind::Method, module, visibility, location);
+                }
+            }
+        }
+    }
+
+    fn resolve_path(&self, from: &str, path: &str, fallback: bool) -> Option<Resolution> {
+        if let Some(external) = path.strip_prefix("::") {
+            return Some(Resolution::External(external.to_string()));
+        }
+        let mut segments = path.split("::");
+        let mut current = match segments.next()? {
+            "crate" => Resolution::Local("crate".to_string()),
+            "self" => Resolution::Local(from.to_string()),
+            "super" => Resolution::Local(parent_of(from)?.to_string()),
+            name => match self.lookup(from, name, from) {
+                Some(resolution) => resolution,
+                None if fallback => Resolution::External(name.to_string()),
+                None => return None,
+            },
+        };
+        for segment in segments {
+            current = match current {
+                Resolution::External(path) => Resolution::External(format!("{}::{}", path, segment)),
+                Resolution::Local(path) if segment == "super" => Resolution::Local(parent_of(&path)?.to_string()),
+                Resolution::Local(path) => self.lookup(&path, segment, from)?,
+            };
+        }
+        Some(current)
+    }
+
+    /// `name` in `scope`, defined there or imported, if visible from module `from`.
+    fn lookup(&self, scope: &str, name: &str, from: &str) -> Option<Resolution> {
+        let path = format!("{}::{}", scope, name);
+        if let Some(symbol) = self.symbols.get(&path) {
+            if visible(&symbol.visibility, &symbol.module, from) {
+                return Some(Resolution::Local(path));
+            }
+        }
+        let binding = self.scopes.get(scope)?.get(name)?;
+        visible(&binding.visibility, scope, from).then(|| binding.resolution.clone())
+    }
+}
+
+/// `crate::a::B` -> `crate::a`; None for a single segment.
+pub fn parent_of(path: &str) -> Option<&str> {
+    path.rsplit_once("::").map(|(parent, _)| parent)
+}
+
+/// Whether `outer` contains `inner`, both spans of the same file.
+fn encloses(outer: &Span, inner: &Span) -> bool {
+    (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+/// Whether an item declared with `visibility` in module `owner` can be named from
+/// module `from`.
+fn visible(visibility: &Visibility, owner: &str, from: &str) -> bool {
+    let scope = match visibility {
+        Visibility::Public | Visibility::Crate => return true,
+        Visibility::Inherited => owner,
+        Visibility::Restricted(path) if path == "self" => owner,
+        Visibility::Restricted(path) if path == "super" => parent_of(owner).unwrap_or(owner),
+        Visibility::Restricted(path) if path == "crate" || path.starts_with("crate::") => path,
+        Visibility::Restricted(_) => return true,
+    };
+    from == scope || from.strip_prefix(scope).is_some_and(|rest| rest.starts_with("::"))
+}
@@ -0,0 +1,243 @@
+use crate::graph::{location_key, NodeIndex, ProjectGraph};
+use crate::symbols::{Resolution, SymbolKind};
+use crate::types::{FunctionSnapshot, Location, RustFileSnapshot, Span};
+use crate::visitor::type_paths;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// A struct, enum, union, trait or type alias of one target.
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeNode {
+    pub crate_idx: usize,
+    pub target_idx: usize,
+    pub path: String, // Symbol path: `crate::graph::ModuleNode`
+    pub kind: SymbolKind,
+    pub location: Location,
+}
+
+/// Where one type mentions another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TypeUse {
+    Field,    // Field of a struct or union
+    Variant,  // Payload of an enum variant
+    Argument, // Argument of a method of the type's impls, or of the trait
+    Return,   // Return type of such a method
+    Impl,     // Implemented trait, or one of its generic arguments
+    Alias,    // Aliased type
+}
+
+impl std::fmt::Display for TypeUse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TypeUse::Field => "field",
+            TypeUse::Variant => "variant",
+            TypeUse::Argument => "argument",
+            TypeUse::Return => "return",
+            TypeUse::Impl => "impl",
+            TypeUse::Alias => "alias",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeEdge {
+    pub from: usize, // Index into `TypeGraph::nodes`
+    pub to: usize,
+    pub kind: TypeUse,
+    pub via: String, // The field, variant, method or trait: `root_module`, `Lib`, `scan`, `From`
+    pub location: Location,
+}
+
+/// Which types of a `ProjectGraph` mention which others, through struct fields, enum
+/// payloads, method signatures, implemented traits and aliases.
+///
+/// Only types of the graph are nodes: `Vec<Node>` is an edge to `Node` alone. Paths
+/// resolve through the symbol tables, into other crates of the graph too. Methods
+/// taking or returning their own type add no edge; recursive fields do.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TypeGraph {
+    pub nodes: Vec<TypeNode>, // By crate, then target, then path
+    pub edges: Vec<TypeEdge>, // By source node
+    #[serde(skip)]
+    index: NodeIndex,
+}
+
+/// A type mentioned by the item at some location, not resolved yet.
+struct Mention<'a> {
+    kind: TypeUse,
+    via: &'a str,
+    ty: &'a str,
+    span: Span,
+}
+
+impl TypeGraph {
+    /// Builds the type graph of `graph`, whose symbol tables must already be built.
+    pub fn build(graph: &ProjectGraph) -> Self {
+        // Types and impls by location, with the types they mention
+        let mut mentions: HashMap<(&str, usize, usize), Vec<Mention>> = HashMap::new();
+        for snapshot in graph.reached_snapshots() {
+            for (span, found) in file_mentions(snapshot) {
+                mentions.insert(location_key(&snapshot.path, &span), found);
+            }
+        }
+
+        let mut nodes = Vec::new();
+        let mut sources = Vec::new(); // (node, module, item location, symbol table)
+        for (crate_idx, crate_node) in graph.crates.iter().enumerate() {
+            for (target_idx, target) in crate_node.targets.iter().enumerate() {
+                let types = target.symbols.symbols().filter(|symbol| target.symbols.is_type(&symbol.path) || symbol.kind == SymbolKind::Trait);
+                for symbol in types {
+                    sources.push((nodes.len(), symbol.module.as_str(), &symbol.location, &target.symbols));
+                    nodes.push(TypeNode { crate_idx, target_idx, path: symbol.path.clone(), kind: symbol.kind, location: symbol.location.clone() });
+                }
+            }
+        }
+
+        let mut index = NodeIndex::new(graph.crates.len(), nodes.iter().map(|node| (node.crate_idx, node.target_idx, node.path.as_str())));
+        // Impl blocks add mentions to their self type
+        for (crate_idx, crate_node) in graph.crates.iter().enumerate() {
+            for (target_idx, target) in crate_node.targets.iter().enumerate() {
+                for imp in target.symbols.impls() {
+                    let Some(node) = index.get(crate_idx, target_idx, &imp.self_type) else { continue };
+                    sources.push((node, imp.module.as_str(), &imp.location, &target.symbols));
+                }
+            }
+        }
+        sources.sort_by_key(|(node, ..)| *node);
+
+        let mut edges = Vec::new();
+        for (from, module, location, table) in sources {
+            let Some(found) = mentions.get(&location_key(&location.path, &location.span)) else { continue };
+            let (crate_idx, target_idx) = (nodes[from].crate_idx, nodes[from].target_idx);
+            for mention in found {
+                for path in type_paths(mention.ty) {
+                    let to = match path.as_str() {
+                        "Self" => Some(from),
+                        path => match table.resolve(module, path) {
+                            Some(Resolution::Local(local)) => index.get(crate_idx, target_idx, &local),
+                            Some(Resolution::External(external)) => graph.find_symbol(crate_idx, &external)
+                                .and_then(|(dep, symbol)| index.node(dep, &symbol.path)),
+                            None => None,
+                        },
+                    };
+                    let Some(to) = to else { continue };
+                    if to == from && matches!(mention.kind, TypeUse::Argument | TypeUse::Return) {
+                        continue;
+                    }
+                    let location = Location { path: location.path.clone(), span: mention.span };
+                    edges.push(TypeEdge { from, to, kind: mention.kind, via: mention.via.to_string(), location });
+                }
+            }
+        }
+        index.link(edges.iter().map(|edge| (edge.from, Some(edge.to))));
+        TypeGraph { nodes, edges, index }
+    }
+
+    /// Node of type `path` of crate `crate_idx`, in its library target if it has one.
+    pub fn node(&self, crate_idx: usize, path: &str) -> Option<usize> {
+        self.index.node(crate_idx, path)
+    }
+
+    /// Where `node` mentions other types.
+    pub fn dependencies(&self, node: usize) -> impl Iterator<Item = &TypeEdge> {
+        self.index.outgoing(node).iter().map(|&edge| &self.edges[edge])
+    }
+
+    /// Where other types mention `node`.
+    pub fn dependents(&self, node: usize) -> impl Iterator<Item = &TypeEdge> {
+        self.index.incoming(node).iter().map(|&edge| &self.edges[edge])
+    }
+
+    /// Graphviz DOT rendering, one cluster per target of `graph`, which the type graph
+    /// must have been built from. Parallel edges are merged into one, labelled with
+    /// every use.
+    pub fn to_dot(&self, graph: &ProjectGraph) -> String {
+        let mut out = String::from("digraph types {\n    rankdir=LR;\n    node [shape=box];\n");
+        let mut clusters: Vec<((usize, usize), Vec<usize>)> = Vec::new();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            match clusters.last_mut() {
+                Some((target, members)) if *target == (node.crate_idx, node.target_idx) => members.push(idx),
+                _ => clusters.push(((node.crate_idx, node.target_idx), vec![idx])),
+            }
+        }
+        for ((crate_idx, target_idx), members) in clusters {
+            let crate_node = &graph.crates[crate_idx];
+            let target = &crate_node.targets[target_idx];
+            let _ = writeln!(out, "    subgraph cluster_{}_{} {{", crate_idx, target_idx);
+            let kind = format!("{:?}", target.kind).to_lowercase();
+            let label = match target.name == crate_node.name {
+                true => format!("{} ({})", crate_node.name, kind),
+                false => format!("{} ({} {})", crate_node.name, kind, target.name),
+            };
+            let _ = writeln!(out, "        label=\"{}\";", label);
+            for idx in members {
+                let node = &self.nodes[idx];
+                let name = node.path.strip_prefix("crate::").unwrap_or(&node.path);
+                let _ = writeln!(out, "        n{} [label=\"{}\"];", idx, name);
+            }
+            out.push_str("    }\n");
+        }
+
+        // Merged edges in order of first appearance, with their distinct labels
+        let mut merged: Vec<((usize, usize), Vec<String>)> = Vec::new();
+        let mut positions: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut labelled = HashSet::new();
+        for edge in &self.edges {
+            let label = format!("{} {}", edge.kind, edge.via);
+            let ends = (edge.from, edge.to);
+            let position = *positions.entry(ends).or_insert_with(|| {
+                merged.push((ends, Vec::new()));
+                merged.len() - 1
+            });
+            if labelled.insert((ends, label.clone())) {
+                merged[position].1.push(label);
+            }
+        }
+        for ((from, to), labels) in merged {
+            let _ = writeln!(out, "    n{} -> n{} [label=\"{}\"];", from, to, labels.join("\\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Mentions of the types, traits, aliases and impls of a file, by the item's span.
+fn file_mentions(snapshot: &RustFileSnapshot) -> Vec<(Span, Vec<Mention<'_>>)> {
+    let mut out = Vec::new();
+    for strct in &snapshot.structs {
+        let fields = strct.fields.iter().map(|field| Mention { kind: TypeUse::Field, via: &field.name, ty: &field.ty, span: field.span });
+        out.push((strct.span, fields.collect()));
+    }
+    for union in &snapshot.unions {
+        let fields = union.fields.iter().map(|field| Mention { kind: TypeUse::Field, via: &field.name, ty: &field.ty, span: field.span });
+        out.push((union.span, fields.collect()));
+    }
+    for enm in &snapshot.enums {
+        let payloads = enm.variants.iter().flat_map(|variant| {
+            variant.fields.iter().map(|field| Mention { kind: TypeUse::Variant, via: &variant.name, ty: &field.ty, span: field.span })
+        });
+        out.push((enm.span, payloads.collect()));
+    }
+    for alias in &snapshot.type_aliases {
+        out.push((alias.span, vec![Mention { kind: TypeUse::Alias, via: &alias.name, ty: &alias.target, span: alias.span }]));
+    }
+    for tr in &snapshot.traits {
+        out.push((tr.span, tr.methods.iter().flat_map(signature_mentions).collect()));
+    }
+    for imp in &snapshot.impls {
+        let mut found: Vec<Mention> = imp.trait_path.iter()
+            .map(|trait_path| Mention { kind: TypeUse::Impl, via: imp.trait_name.as_deref().unwrap_or_default(), ty: trait_path, span: imp.span })
+            .collect();
+        found.extend(imp.methods.iter().flat_map(signature_mentions));
+        out.push((imp.span, found));
+    }
+    out
+}
+
+fn signature_mentions(method: &FunctionSnapshot) -> impl Iterator<Item = Mention<'_>> {
+    let inputs = method.signature.inputs.iter().map(|arg| Mention { kind: TypeUse::Argument, via: &method.name, ty: &arg.ty, span: method.span });
+    let output = method.signature.output.iter().map(|ty| Mention { kind: TypeUse::Return, via: &method.name, ty, span: method.span });
+    inputs.chain(output)
+}
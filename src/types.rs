@@ -1,46 +1,405 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RustFileSnapshot {
     pub path: String,
+    pub docs: Option<String>, // Inner `//!` docs of the file
+    pub modules: Vec<ModuleSnapshot>,
     pub functions: Vec<FunctionSnapshot>,
     pub structs: Vec<StructSnapshot>,
     pub traits: Vec<TraitSnapshot>,
     pub enums: Vec<EnumSnapshot>,
     pub impls: Vec<ImplSnapshot>,
+    pub consts: Vec<ConstSnapshot>,
+    pub statics: Vec<StaticSnapshot>,
+    pub type_aliases: Vec<TypeAliasSnapshot>,
+    pub unions: Vec<UnionSnapshot>,
+    pub macros: Vec<MacroSnapshot>,
+    pub extern_blocks: Vec<ExternBlockSnapshot>,
+    pub uses: Vec<UseSnapshot>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Source location of an item. Lines and columns are 1-based, like rustc diagnostics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FunctionSnapshot {
     pub name: String,
     pub args: Vec<String>,
-    pub variables: Vec<(String, Option<String>)>,
+    pub signature: FunctionSignature,
+    pub variables: Vec<VariableSnapshot>,
+    pub calls: Vec<CallSnapshot>, // Calls in the body, closures and macro arguments included, in source order
+    pub owner: Option<String>,      // Self type for impl methods
+    pub trait_name: Option<String>, // Implemented or declaring trait
+    pub visibility: Visibility,
+    pub attributes: Vec<String>, // Non-doc, non-derive attributes as written: `cfg(test)`, `deprecated`
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionSignature {
+    pub is_const: bool,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    pub abi: Option<String>, // `extern "C"` -> "C"; a bare `extern` is also "C"
+    pub generics: Vec<String>,
+    pub receiver: Option<String>, // `&self`, `&mut self`, `self: Box<Self>`, ...
+    pub inputs: Vec<ArgSnapshot>,
+    pub output: Option<String>,
+    pub where_clause: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArgSnapshot {
+    pub pattern: String,
+    pub ty: String,
+    pub type_path: Option<String>, // Path of the named type `ty` is built on: `crate::a::Foo` for `&mut crate::a::Foo<T>`
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariableSnapshot {
+    pub name: String,
+    pub ty: Option<String>,
+    pub type_path: Option<String>, // Path of the named type `ty` is built on
+    pub mutable: bool,
+    pub scope_depth: usize, // 0 = function body, +1 per nested block, arm or closure
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StructSnapshot {
     pub name: String,
-    pub fields: Vec<String>,
+    pub kind: StructKind,
+    pub fields: Vec<FieldSnapshot>,
     pub methods: Vec<String>,
+    pub impls: Vec<ImplRef>, // Every impl block for this type, wherever it lives in the crate
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub derives: Vec<String>, // `#[derive(..)]` paths as written: `Debug`, `serde::Serialize`
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TraitSnapshot {
     pub name: String,
-    pub methods: Vec<String>,
+    pub methods: Vec<FunctionSnapshot>,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EnumSnapshot {
     pub name: String,
-    pub variants: Vec<String>,
+    pub variants: Vec<VariantSnapshot>,
     pub methods: Vec<String>,
+    pub impls: Vec<ImplRef>,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub derives: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+/// Shape of a struct or enum variant body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StructKind {
+    Named, // `{ a: T }`
+    Tuple, // `(T, U)`
+    #[default]
+    Unit,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldSnapshot {
+    pub name: String, // Position (`0`, `1`, ...) for tuple fields
+    pub ty: String,
+    pub type_path: Option<String>, // Path of the named type `ty` is built on
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantSnapshot {
+    pub name: String,
+    pub kind: StructKind,
+    pub fields: Vec<FieldSnapshot>,
+    pub discriminant: Option<String>,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+/// A call in a function body: `path(..)` or `receiver.method(..)`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallSnapshot {
+    pub callee: String, // Path as written, without generic arguments: `Self::new`, `fs::read`; the name for method calls
+    pub receiver: Option<String>, // `self`, a variable or `self.field` a method is called on; None for other receivers
+    pub is_method: bool,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ImplSnapshot {
-    pub for_type: String,
+    pub for_type: String,             // Base type name: `Foo` for `impl<T> Trait for &crate::a::Foo<T>`
+    pub self_ty: String,              // Self type as written: `&crate::a::Foo<T>`
+    pub self_path: Option<String>,    // Path of the named self type: `crate::a::Foo`
+    pub type_args: Vec<String>,       // Generic arguments of the self type: `T`
+    pub generics: Vec<String>,        // Parameters declared on the impl: `T: Clone`
+    pub trait_name: Option<String>,   // Last segment of the trait path: `From`
+    pub trait_path: Option<String>,   // Trait path as written: `std::convert::From<String>`
+    pub trait_args: Vec<String>,      // Generic arguments of the trait: `String`
+    pub methods: Vec<FunctionSnapshot>,
+    pub target: Option<Location>, // Definition of `for_type`, once linked by the engine
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+/// Declared visibility of an item or field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Visibility {
+    Public,             // `pub`
+    Crate,              // `pub(crate)`
+    Restricted(String), // `pub(super)`, `pub(self)`, `pub(in a::b)`: the path
+    #[default]
+    Inherited,          // No modifier: private, or public for trait items and enum variants
+}
+
+/// A position in a specific file, for links between snapshots of different files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub path: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImplRef {
     pub trait_name: Option<String>,
-    pub methods: Vec<String>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstSnapshot {
+    pub name: String,
+    pub ty: String,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StaticSnapshot {
+    pub name: String,
+    pub ty: String,
+    pub mutable: bool,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeAliasSnapshot {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub target: String,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnionSnapshot {
+    pub name: String,
+    pub fields: Vec<FieldSnapshot>,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub derives: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+/// A `mod` item, declared (`mod foo;`) or inline (`mod foo { ... }`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleSnapshot {
+    pub name: String,
+    pub inline: bool,
+    pub visibility: Visibility,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>, // Outer docs, plus inner docs for inline modules
+    pub span: Span,
+}
+
+/// A `macro_rules!` definition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MacroSnapshot {
+    pub name: String,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternBlockSnapshot {
+    pub abi: Option<String>,
+    pub functions: Vec<FunctionSnapshot>,
+    pub statics: Vec<StaticSnapshot>,
+    pub attributes: Vec<String>,
+    pub docs: Option<String>,
+    pub span: Span,
+}
+
+/// One leaf of a `use` tree: `use a::{b, c as d, e::*}` yields three entries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UseSnapshot {
+    pub path: String, // `a::e` for the glob above, `a` for `use a::{self}`
+    pub alias: Option<String>,
+    pub is_glob: bool,
+    pub visibility: Visibility, // `pub use` re-exports
+    pub attributes: Vec<String>,
+    pub span: Span,
+}
+
+impl RustFileSnapshot {
+    /// Free functions followed by impl methods and trait methods, in source order per group.
+    pub fn all_functions(&self) -> impl Iterator<Item = &FunctionSnapshot> {
+        self.functions.iter()
+            .chain(self.impls.iter().flat_map(|imp| imp.methods.iter()))
+            .chain(self.traits.iter().flat_map(|tr| tr.methods.iter()))
+    }
+}
+
+/// First paragraph of a doc comment, joined into one line.
+pub fn doc_summary(docs: Option<&str>) -> Option<String> {
+    let summary = docs?
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if summary.is_empty() { None } else { Some(summary) }
+}
+
+impl Visibility {
+    pub fn is_public(&self) -> bool {
+        matches!(self, Visibility::Public)
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "pub"),
+            Visibility::Crate => write!(f, "pub(crate)"),
+            Visibility::Restricted(path) if path == "self" || path == "super" => write!(f, "pub({})", path),
+            Visibility::Restricted(path) => write!(f, "pub(in {})", path),
+            Visibility::Inherited => Ok(()),
+        }
+    }
+}
+
+/// Whether `attributes` holds the named attribute, bare or with arguments: `has_attribute(attrs, "cfg")`.
+pub fn has_attribute(attributes: &[String], name: &str) -> bool {
+    attributes.iter().any(|attr| {
+        attr.strip_prefix(name).is_some_and(|rest| rest.is_empty() || rest.starts_with(['(', ' ', '=']))
+    })
+}
+
+impl ImplSnapshot {
+    /// Header as it reads in source, e.g. `impl<T> From<T> for Wrapper<T>`.
+    pub fn header(&self) -> String {
+        let mut out = String::from("impl");
+        if !self.generics.is_empty() {
+            out.push_str(&format!("<{}>", self.generics.join(", ")));
+        }
+        out.push(' ');
+        if let Some(trait_path) = &self.trait_path {
+            out.push_str(&format!("{} for ", trait_path));
+        }
+        out.push_str(&self.self_ty);
+        out
+    }
+}
+
+impl FieldSnapshot {
+    /// Tuple fields are named by position.
+    pub fn is_positional(&self) -> bool {
+        self.name.starts_with(|c: char| c.is_ascii_digit())
+    }
+}
+
+impl FunctionSnapshot {
+    /// `Type::method` for impl methods, `Trait::method` for trait methods, plain name otherwise.
+    pub fn qualified_name(&self) -> String {
+        match self.owner.as_ref().or(self.trait_name.as_ref()) {
+            Some(owner) => format!("{}::{}", owner, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Renders the signature as it reads in source, e.g. `async fn get<T>(&self, id: u64) -> T`.
+    pub fn signature_string(&self) -> String {
+        let sig = &self.signature;
+        let mut out = String::new();
+        if sig.is_const {
+            out.push_str("const ");
+        }
+        if sig.is_async {
+            out.push_str("async ");
+        }
+        if sig.is_unsafe {
+            out.push_str("unsafe ");
+        }
+        if let Some(abi) = &sig.abi {
+            out.push_str(&format!("extern \"{}\" ", abi));
+        }
+        out.push_str("fn ");
+        out.push_str(&self.name);
+        if !sig.generics.is_empty() {
+            out.push_str(&format!("<{}>", sig.generics.join(", ")));
+        }
+
+        let params: Vec<String> = sig.receiver.iter().cloned()
+            .chain(sig.inputs.iter().map(|arg| format!("{}: {}", arg.pattern, arg.ty)))
+            .collect();
+        out.push_str(&format!("({})", params.join(", ")));
+
+        if let Some(output) = &sig.output {
+            out.push_str(&format!(" -> {}", output));
+        }
+        if let Some(where_clause) = &sig.where_clause {
+            out.push(' ');
+            out.push_str(where_clause);
+        }
+        out
+    }
+}
+
+impl Span {
+    /// `path:line` form understood by editors and CI annotations.
+    pub fn location(&self, path: &str) -> String {
+        format!("{}:{}", path, self.start_line)
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.span.location(&self.path))
+    }
 }
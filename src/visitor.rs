@@ -1,57 +1,67 @@
-use crate::types::{FunctionSnapshot, StructSnapshot, TraitSnapshot, EnumSnapshot, ImplSnapshot};
+use crate::types::{
+    FunctionSnapshot, FunctionSignature, ArgSnapshot, CallSnapshot, StructSnapshot, TraitSnapshot, EnumSnapshot, ImplSnapshot,
+    Span, VariableSnapshot, ConstSnapshot, StaticSnapshot, TypeAliasSnapshot, UnionSnapshot, MacroSnapshot,
+    ExternBlockSnapshot, UseSnapshot, FieldSnapshot, VariantSnapshot, StructKind, ModuleSnapshot, Visibility,
+};
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
 use syn::{visit::Visit, ItemFn, ItemStruct, ItemTrait, Pat, ItemEnum, ItemImpl, ImplItem, Type};
 use quote::ToTokens;
 
 #[derive(Default)]
 pub struct SnapshotVisitor {
+    pub docs: Option<String>,
+    pub modules: Vec<ModuleSnapshot>,
     pub functions: Vec<FunctionSnapshot>,
     pub structs: Vec<StructSnapshot>,
     pub traits: Vec<TraitSnapshot>,
     pub enums: Vec<EnumSnapshot>,
     pub impls: Vec<ImplSnapshot>,
+    pub consts: Vec<ConstSnapshot>,
+    pub statics: Vec<StaticSnapshot>,
+    pub type_aliases: Vec<TypeAliasSnapshot>,
+    pub unions: Vec<UnionSnapshot>,
+    pub macros: Vec<MacroSnapshot>,
+    pub extern_blocks: Vec<ExternBlockSnapshot>,
+    pub uses: Vec<UseSnapshot>,
 }
 
 impl Visit<'_> for SnapshotVisitor {
-    fn visit_item_fn(&mut self, node: &'_ ItemFn) {
-        let name = node.sig.ident.to_string();
-        
-        let mut args = Vec::new();
-        for input in &node.sig.inputs {
-             if let syn::FnArg::Typed(pat_type) = input {
-                 if let Pat::Ident(pat_ident) = &*pat_type.pat {
-                     args.push(pat_ident.ident.to_string());
-                 }
-             }
-        }
-
-        let mut variables = Vec::new();
-        for stmt in &node.block.stmts {
-            if let syn::Stmt::Local(local) = stmt {
-                let (pat, ty_str) = match &local.pat {
-                    Pat::Type(pat_type) => (&*pat_type.pat, Some((&*pat_type.ty).to_token_stream().to_string())),
-                    p => (p, None),
-                };
+    fn visit_file(&mut self, node: &'_ syn::File) {
+        self.docs = docs_of(&node.attrs);
+        syn::visit::visit_file(self, node);
+    }
 
-                if let Pat::Ident(pat_ident) = pat {
-                    let var_name = pat_ident.ident.to_string();
-                    variables.push((var_name, ty_str));
-                }
-            }
-        }
-        self.functions.push(FunctionSnapshot { name, args, variables });
+    fn visit_item_mod(&mut self, node: &'_ syn::ItemMod) {
+        self.modules.push(ModuleSnapshot {
+            name: node.ident.to_string(),
+            inline: node.content.is_some(),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+        syn::visit::visit_item_mod(self, node);
+    }
+
+    fn visit_item_fn(&mut self, node: &'_ ItemFn) {
+        self.functions.push(function_snapshot(&node.sig, Some(&node.block), &node.vis, &node.attrs, span_of(node)));
     }
 
     fn visit_item_struct(&mut self, node: &'_ ItemStruct) {
         let name = node.ident.to_string();
-        let mut fields = Vec::new();
-        if let syn::Fields::Named(fields_named) = &node.fields {
-            for field in &fields_named.named {
-                if let Some(ident) = &field.ident {
-                    fields.push(ident.to_string());
-                }
-            }
-        }
-        self.structs.push(StructSnapshot { name, fields, methods: Vec::new() });
+        let (kind, fields) = fields_of(&node.fields);
+        self.structs.push(StructSnapshot {
+            name,
+            kind,
+            fields,
+            methods: Vec::new(),
+            impls: Vec::new(),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            derives: derives_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
     }
 
     fn visit_item_trait(&mut self, node: &'_ ItemTrait) {
@@ -59,40 +69,751 @@ impl Visit<'_> for SnapshotVisitor {
         let mut methods = Vec::new();
         for item in &node.items {
             if let syn::TraitItem::Fn(method) = item {
-                methods.push(method.sig.ident.to_string());
+                let mut func = function_snapshot(&method.sig, method.default.as_ref(), &syn::Visibility::Inherited, &method.attrs, span_of(method));
+                func.trait_name = Some(name.clone());
+                methods.push(func);
             }
         }
-        self.traits.push(TraitSnapshot { name, methods });
+        self.traits.push(TraitSnapshot {
+            name,
+            methods,
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
     }
 
     fn visit_item_enum(&mut self, node: &'_ ItemEnum) {
         let name = node.ident.to_string();
         let mut variants = Vec::new();
         for variant in &node.variants {
-            variants.push(variant.ident.to_string());
+            let (kind, fields) = fields_of(&variant.fields);
+            variants.push(VariantSnapshot {
+                name: variant.ident.to_string(),
+                kind,
+                fields,
+                discriminant: variant.discriminant.as_ref().map(|(_, expr)| tokens_to_string(expr)),
+                attributes: attributes_of(&variant.attrs),
+                docs: docs_of(&variant.attrs),
+                span: span_of(variant),
+            });
         }
-        self.enums.push(EnumSnapshot { name, variants, methods: Vec::new() });
+        self.enums.push(EnumSnapshot {
+            name,
+            variants,
+            methods: Vec::new(),
+            impls: Vec::new(),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            derives: derives_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
     }
 
     fn visit_item_impl(&mut self, node: &'_ ItemImpl) {
-        let for_type = if let Type::Path(path) = *node.self_ty.clone() {
-            path.path.get_ident().map(|ident| ident.to_string())
-        } else {
-            None
-        };
+        let (for_type, type_args) = base_type(&node.self_ty);
 
-        let trait_name = node.trait_.as_ref().map(|( _, path, _ )| path.segments.last().map(|seg| seg.ident.to_string())).flatten();
+        let trait_path = node.trait_.as_ref().map(|(_, path, _)| path);
+        let trait_name = trait_path.and_then(|path| path.segments.last().map(|seg| seg.ident.to_string()));
+        let trait_args = trait_path
+            .and_then(|path| path.segments.last())
+            .map(|seg| path_args(&seg.arguments))
+            .unwrap_or_default();
 
         let mut methods = Vec::new();
         for item in &node.items {
             if let ImplItem::Fn(method) = item {
-                methods.push(method.sig.ident.to_string());
+                let mut func = function_snapshot(&method.sig, Some(&method.block), &method.vis, &method.attrs, span_of(method));
+                func.owner = Some(for_type.clone());
+                func.trait_name = trait_name.clone();
+                methods.push(func);
+            }
+        }
+        self.impls.push(ImplSnapshot {
+            for_type,
+            self_ty: tokens_to_string(&node.self_ty),
+            self_path: type_path_of(&node.self_ty),
+            type_args,
+            generics: node.generics.params.iter().map(tokens_to_string).collect(),
+            trait_name,
+            trait_path: trait_path.map(tokens_to_string),
+            trait_args,
+            methods,
+            target: None,
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_const(&mut self, node: &'_ syn::ItemConst) {
+        self.consts.push(ConstSnapshot {
+            name: node.ident.to_string(),
+            ty: tokens_to_string(&node.ty),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_static(&mut self, node: &'_ syn::ItemStatic) {
+        self.statics.push(StaticSnapshot {
+            name: node.ident.to_string(),
+            ty: tokens_to_string(&node.ty),
+            mutable: matches!(node.mutability, syn::StaticMutability::Mut(_)),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_type(&mut self, node: &'_ syn::ItemType) {
+        self.type_aliases.push(TypeAliasSnapshot {
+            name: node.ident.to_string(),
+            generics: node.generics.params.iter().map(tokens_to_string).collect(),
+            target: tokens_to_string(&node.ty),
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_union(&mut self, node: &'_ syn::ItemUnion) {
+        let (_, fields) = fields_of(&syn::Fields::Named(node.fields.clone()));
+        self.unions.push(UnionSnapshot {
+            name: node.ident.to_string(),
+            fields,
+            visibility: visibility_of(&node.vis),
+            attributes: attributes_of(&node.attrs),
+            derives: derives_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_macro(&mut self, node: &'_ syn::ItemMacro) {
+        if let Some(ident) = &node.ident {
+            if node.mac.path.is_ident("macro_rules") {
+                self.macros.push(MacroSnapshot {
+                    name: ident.to_string(),
+                    attributes: attributes_of(&node.attrs),
+                    docs: docs_of(&node.attrs),
+                    span: span_of(node),
+                });
+            }
+        }
+    }
+
+    fn visit_item_foreign_mod(&mut self, node: &'_ syn::ItemForeignMod) {
+        let mut functions = Vec::new();
+        let mut statics = Vec::new();
+        for item in &node.items {
+            match item {
+                syn::ForeignItem::Fn(func) => functions.push(function_snapshot(&func.sig, None, &func.vis, &func.attrs, span_of(func))),
+                syn::ForeignItem::Static(stat) => statics.push(StaticSnapshot {
+                    name: stat.ident.to_string(),
+                    ty: tokens_to_string(&stat.ty),
+                    mutable: matches!(stat.mutability, syn::StaticMutability::Mut(_)),
+                    visibility: visibility_of(&stat.vis),
+                    attributes: attributes_of(&stat.attrs),
+                    docs: docs_of(&stat.attrs),
+                    span: span_of(stat),
+                }),
+                _ => {}
+            }
+        }
+        self.extern_blocks.push(ExternBlockSnapshot {
+            abi: Some(node.abi.name.as_ref().map(|name| name.value()).unwrap_or_else(|| "C".to_string())),
+            functions,
+            statics,
+            attributes: attributes_of(&node.attrs),
+            docs: docs_of(&node.attrs),
+            span: span_of(node),
+        });
+    }
+
+    fn visit_item_use(&mut self, node: &'_ syn::ItemUse) {
+        let prefix = if node.leading_colon.is_some() { "::" } else { "" };
+        let start = self.uses.len();
+        flatten_use_tree(&node.tree, prefix.to_string(), span_of(node), &mut self.uses);
+        for use_snapshot in &mut self.uses[start..] {
+            use_snapshot.visibility = visibility_of(&node.vis);
+            use_snapshot.attributes = attributes_of(&node.attrs);
+        }
+    }
+}
+
+/// Reduces an impl's self type to the name impls are grouped under, plus its generic
+/// arguments. References, pointers and parentheses are looked through (`&mut Foo<T>`
+/// is `Foo` with `[T]`); paths keep only their last segment. Types without a name of
+/// their own (slices, arrays, tuples, trait objects, fn pointers) use their rendered form.
+fn base_type(ty: &Type) -> (String, Vec<String>) {
+    match ty {
+        Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(last) => (last.ident.to_string(), path_args(&last.arguments)),
+            None => (tokens_to_string(ty), Vec::new()),
+        },
+        Type::Reference(reference) => base_type(&reference.elem),
+        Type::Ptr(ptr) => base_type(&ptr.elem),
+        Type::Paren(paren) => base_type(&paren.elem),
+        Type::Group(group) => base_type(&group.elem),
+        _ => (tokens_to_string(ty), Vec::new()),
+    }
+}
+
+/// Path of the named type `ty` is built on, looking through references, pointers and
+/// parentheses: `&'a mut crate::a::Foo<T>` is `crate::a::Foo`. None for types without
+/// one: tuples, slices, arrays, trait objects, `impl Trait`, fn pointers and qualified paths.
+fn type_path_of(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => Some(path_string(&type_path.path)),
+        Type::Reference(reference) => type_path_of(&reference.elem),
+        Type::Ptr(ptr) => type_path_of(&ptr.elem),
+        Type::Paren(paren) => type_path_of(&paren.elem),
+        Type::Group(group) => type_path_of(&group.elem),
+        _ => None,
+    }
+}
+
+fn path_args(arguments: &syn::PathArguments) -> Vec<String> {
+    match arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().map(tokens_to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn fields_of(fields: &syn::Fields) -> (StructKind, Vec<FieldSnapshot>) {
+    let kind = match fields {
+        syn::Fields::Named(_) => StructKind::Named,
+        syn::Fields::Unnamed(_) => StructKind::Tuple,
+        syn::Fields::Unit => StructKind::Unit,
+    };
+    let fields = fields.iter().enumerate().map(|(index, field)| FieldSnapshot {
+        name: field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_else(|| index.to_string()),
+        ty: tokens_to_string(&field.ty),
+        type_path: type_path_of(&field.ty),
+        visibility: visibility_of(&field.vis),
+        attributes: attributes_of(&field.attrs),
+        docs: docs_of(&field.attrs),
+        span: span_of(field),
+    }).collect();
+    (kind, fields)
+}
+
+fn flatten_use_tree(tree: &syn::UseTree, prefix: String, span: Span, out: &mut Vec<UseSnapshot>) {
+    let join = |name: &str| {
+        if prefix.is_empty() || prefix == "::" {
+            format!("{}{}", prefix, name)
+        } else {
+            format!("{}::{}", prefix, name)
+        }
+    };
+    match tree {
+        syn::UseTree::Path(path) => flatten_use_tree(&path.tree, join(&path.ident.to_string()), span, out),
+        // `use a::{self}` imports `a` itself
+        syn::UseTree::Name(name) if name.ident == "self" => {
+            out.push(UseSnapshot { path: prefix, alias: None, is_glob: false, span, ..UseSnapshot::default() });
+        }
+        syn::UseTree::Name(name) => {
+            out.push(UseSnapshot { path: join(&name.ident.to_string()), alias: None, is_glob: false, span, ..UseSnapshot::default() });
+        }
+        syn::UseTree::Rename(rename) => {
+            let path = if rename.ident == "self" { prefix.clone() } else { join(&rename.ident.to_string()) };
+            out.push(UseSnapshot { path, alias: Some(rename.rename.to_string()), is_glob: false, span, ..UseSnapshot::default() });
+        }
+        syn::UseTree::Glob(_) => {
+            out.push(UseSnapshot { path: prefix, alias: None, is_glob: true, span, ..UseSnapshot::default() });
+        }
+        syn::UseTree::Group(group) => {
+            for item in &group.items {
+                flatten_use_tree(item, prefix.clone(), span, out);
+            }
+        }
+    }
+}
+
+/// Shared extraction for free functions, impl methods and trait methods.
+/// Trait methods without a default body have no variables.
+fn function_snapshot(
+    sig: &syn::Signature,
+    block: Option<&syn::Block>,
+    vis: &syn::Visibility,
+    attrs: &[syn::Attribute],
+    span: Span,
+) -> FunctionSnapshot {
+    let mut args = Vec::new();
+    for input in &sig.inputs {
+        if let syn::FnArg::Typed(pat_type) = input {
+            if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                args.push(pat_ident.ident.to_string());
+            }
+        }
+    }
+
+    let mut collector = VariableCollector::default();
+    let mut calls = CallCollector::default();
+    if let Some(block) = block {
+        // Statements of the body itself are depth 0; nested blocks go deeper
+        for stmt in &block.stmts {
+            collector.visit_stmt(stmt);
+        }
+        calls.visit_block(block);
+    }
+
+    FunctionSnapshot {
+        name: sig.ident.to_string(),
+        args,
+        signature: signature_of(sig),
+        variables: collector.variables,
+        calls: calls.calls,
+        owner: None,
+        trait_name: None,
+        visibility: visibility_of(vis),
+        attributes: attributes_of(attrs),
+        docs: docs_of(attrs),
+        span,
+    }
+}
+
+/// Collects variable bindings at every scope depth of a function body: `let`
+/// statements, `match` arms, `if let`/`while let`, `for` loops and closure parameters.
+#[derive(Default)]
+struct VariableCollector {
+    depth: usize,
+    variables: Vec<VariableSnapshot>,
+}
+
+impl VariableCollector {
+    /// `refutable` patterns (match arms, `if let`) treat capitalized identifiers as
+    /// enum variants or constants rather than new bindings.
+    fn collect_pat(&mut self, pat: &Pat, ty: Option<&Type>, depth: usize, refutable: bool) {
+        match pat {
+            Pat::Ident(pat_ident) => {
+                let name = pat_ident.ident.to_string();
+                let is_path_like = refutable
+                    && pat_ident.subpat.is_none()
+                    && pat_ident.mutability.is_none()
+                    && pat_ident.by_ref.is_none()
+                    && name.starts_with(char::is_uppercase);
+                if !is_path_like {
+                    self.variables.push(VariableSnapshot {
+                        name,
+                        ty: ty.map(tokens_to_string),
+                        type_path: ty.and_then(type_path_of),
+                        mutable: pat_ident.mutability.is_some(),
+                        scope_depth: depth,
+                        span: span_of(&pat_ident.ident),
+                    });
+                }
+                if let Some((_, subpat)) = &pat_ident.subpat {
+                    self.collect_pat(subpat, None, depth, refutable);
+                }
+            }
+            Pat::Type(pat_type) => self.collect_pat(&pat_type.pat, Some(&pat_type.ty), depth, refutable),
+            Pat::Tuple(tuple) => {
+                // `let (a, b): (u8, String)` hands each element its own type
+                let elem_types: Vec<Option<&Type>> = match ty {
+                    Some(Type::Tuple(tuple_ty)) if tuple_ty.elems.len() == tuple.elems.len() => {
+                        tuple_ty.elems.iter().map(Some).collect()
+                    }
+                    _ => vec![None; tuple.elems.len()],
+                };
+                for (elem, elem_ty) in tuple.elems.iter().zip(elem_types) {
+                    self.collect_pat(elem, elem_ty, depth, refutable);
+                }
+            }
+            Pat::TupleStruct(tuple_struct) => {
+                for elem in &tuple_struct.elems {
+                    self.collect_pat(elem, None, depth, refutable);
+                }
+            }
+            Pat::Struct(pat_struct) => {
+                for field in &pat_struct.fields {
+                    self.collect_pat(&field.pat, None, depth, refutable);
+                }
+            }
+            Pat::Slice(slice) => {
+                for elem in &slice.elems {
+                    self.collect_pat(elem, None, depth, refutable);
+                }
+            }
+            Pat::Reference(reference) => self.collect_pat(&reference.pat, None, depth, refutable),
+            Pat::Paren(paren) => self.collect_pat(&paren.pat, ty, depth, refutable),
+            // Every alternative binds the same names, so the first one is enough
+            Pat::Or(or) => {
+                if let Some(first) = or.cases.first() {
+                    self.collect_pat(first, ty, depth, refutable);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visit<'_> for VariableCollector {
+    fn visit_block(&mut self, node: &'_ syn::Block) {
+        self.depth += 1;
+        syn::visit::visit_block(self, node);
+        self.depth -= 1;
+    }
+
+    fn visit_local(&mut self, node: &'_ syn::Local) {
+        self.collect_pat(&node.pat, None, self.depth, false);
+        if let Some(init) = &node.init {
+            self.visit_expr(&init.expr);
+            if let Some((_, diverge)) = &init.diverge {
+                self.visit_expr(diverge);
+            }
+        }
+    }
+
+    // `if let` / `while let` bindings live in the block that follows
+    fn visit_expr_let(&mut self, node: &'_ syn::ExprLet) {
+        self.visit_expr(&node.expr);
+        self.collect_pat(&node.pat, None, self.depth + 1, true);
+    }
+
+    fn visit_arm(&mut self, node: &'_ syn::Arm) {
+        self.collect_pat(&node.pat, None, self.depth + 1, true);
+        self.depth += 1;
+        if let Some((_, guard)) = &node.guard {
+            self.visit_expr(guard);
+        }
+        // A block body is the arm's own scope rather than a further level
+        match &*node.body {
+            syn::Expr::Block(block) => {
+                for stmt in &block.block.stmts {
+                    self.visit_stmt(stmt);
+                }
+            }
+            body => self.visit_expr(body),
+        }
+        self.depth -= 1;
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'_ syn::ExprForLoop) {
+        self.visit_expr(&node.expr);
+        self.collect_pat(&node.pat, None, self.depth + 1, false);
+        self.visit_block(&node.body);
+    }
+
+    fn visit_expr_closure(&mut self, node: &'_ syn::ExprClosure) {
+        for input in &node.inputs {
+            self.collect_pat(input, None, self.depth + 1, false);
+        }
+        match &*node.body {
+            syn::Expr::Block(block) => self.visit_block(&block.block),
+            body => {
+                self.depth += 1;
+                self.visit_expr(body);
+                self.depth -= 1;
+            }
+        }
+    }
+
+    // Nested items are not part of the enclosing function's scope
+    fn visit_item(&mut self, _node: &'_ syn::Item) {}
+}
+
+/// Collects the calls of a function body, closures included. Macro arguments are
+/// searched too when they parse as comma-separated expressions, as in `format!` or `vec!`.
+#[derive(Default)]
+struct CallCollector {
+    calls: Vec<CallSnapshot>,
+}
+
+impl Visit<'_> for CallCollector {
+    fn visit_expr_call(&mut self, node: &'_ syn::ExprCall) {
+        // `<T as Trait>::f(..)` names no path of its own; closures and other callee
+        // expressions are not calls to an item
+        if let syn::Expr::Path(path) = &*node.func {
+            if path.qself.is_none() {
+                self.calls.push(CallSnapshot {
+                    callee: path_string(&path.path),
+                    receiver: None,
+                    is_method: false,
+                    span: span_of(node),
+                });
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'_ syn::ExprMethodCall) {
+        self.calls.push(CallSnapshot {
+            callee: node.method.to_string(),
+            receiver: receiver_of(&node.receiver),
+            is_method: true,
+            span: span_of(node),
+        });
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'_ syn::Macro) {
+        let args = node.parse_body_with(syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated);
+        for arg in args.iter().flatten() {
+            self.visit_expr(arg);
+        }
+    }
+
+    // Nested items are not part of the enclosing function
+    fn visit_item(&mut self, _node: &'_ syn::Item) {}
+}
+
+/// `a::b::c` without generic arguments, keeping a leading `::`.
+fn path_string(path: &syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|seg| seg.ident.to_string()).collect();
+    let prefix = if path.leading_colon.is_some() { "::" } else { "" };
+    format!("{}{}", prefix, segments.join("::"))
+}
+
+/// Every path a type written as `ty` mentions, outermost first:
+/// `HashMap<String, Vec<crate::a::Node>>` -> `HashMap`, `String`, `Vec`, `crate::a::Node`.
+/// Empty if `ty` does not parse as a type.
+pub fn type_paths(ty: &str) -> Vec<String> {
+    #[derive(Default)]
+    struct PathCollector {
+        paths: Vec<String>,
+    }
+
+    impl Visit<'_> for PathCollector {
+        fn visit_path(&mut self, node: &'_ syn::Path) {
+            self.paths.push(path_string(node));
+            syn::visit::visit_path(self, node);
+        }
+    }
+
+    let mut collector = PathCollector::default();
+    if let Ok(ty) = syn::parse_str::<Type>(ty) {
+        collector.visit_type(&ty);
+    }
+    collector.paths
+}
+
+/// `self`, `name` or `self.field`: receivers whose type a function's signature and
+/// variables can tell.
+fn receiver_of(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) if path.qself.is_none() => path.path.get_ident().map(|ident| ident.to_string()),
+        syn::Expr::Field(field) => match (&*field.base, &field.member) {
+            (syn::Expr::Path(base), syn::Member::Named(name)) if base.path.is_ident("self") => Some(format!("self.{}", name)),
+            _ => None,
+        },
+        syn::Expr::Paren(paren) => receiver_of(&paren.expr),
+        _ => None,
+    }
+}
+
+fn signature_of(sig: &syn::Signature) -> FunctionSignature {
+    let mut receiver = None;
+    let mut inputs = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            syn::FnArg::Receiver(recv) => {
+                receiver = Some(if recv.colon_token.is_some() {
+                    let mutability = if recv.mutability.is_some() { "mut " } else { "" };
+                    format!("{}self: {}", mutability, tokens_to_string(&recv.ty))
+                } else {
+                    tokens_to_string(recv)
+                });
+            }
+            syn::FnArg::Typed(pat_type) => inputs.push(ArgSnapshot {
+                pattern: tokens_to_string(&pat_type.pat),
+                ty: tokens_to_string(&pat_type.ty),
+                type_path: type_path_of(&pat_type.ty),
+            }),
+        }
+    }
+
+    let output = match &sig.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ty) => Some(tokens_to_string(ty)),
+    };
+
+    FunctionSignature {
+        is_const: sig.constness.is_some(),
+        is_async: sig.asyncness.is_some(),
+        is_unsafe: sig.unsafety.is_some(),
+        abi: sig.abi.as_ref().map(|abi| abi.name.as_ref().map(|name| name.value()).unwrap_or_else(|| "C".to_string())),
+        generics: sig.generics.params.iter().map(tokens_to_string).collect(),
+        receiver,
+        inputs,
+        output,
+        where_clause: sig.generics.where_clause.as_ref().map(tokens_to_string),
+    }
+}
+
+/// Renders a syntax node as compact Rust source (`Vec<&'a str>` rather than the
+/// `Vec < & 'a str >` that `TokenStream::to_string` produces).
+pub fn tokens_to_string<T: ToTokens>(node: &T) -> String {
+    let mut out = String::new();
+    write_tokens(node.to_token_stream(), &mut out);
+    out
+}
+
+fn write_tokens(tokens: TokenStream, out: &mut String) {
+    // `glue_next`: the previous token binds to whatever follows (`&`, `<`, `::`, `'a`, ...).
+    // `prev_operand`: the previous token was an ident, literal or group, which decides
+    // whether `<`, `(`, `?` and friends attach to it.
+    // `angle_depth` counts open generic brackets, telling `Vec<T>` apart from `1 << 3`.
+    let mut glue_next = true;
+    let mut prev_ident = false;
+    let mut prev_operand = false;
+    let mut angle_depth = 0usize;
+
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                let opens_generics = c == '<'
+                    && (prev_ident || out.is_empty() || out.ends_with("::") || out.ends_with('&'));
+                let closes_generics = c == '>' && angle_depth > 0 && !out.ends_with('-');
+                let glue_before = match c {
+                    ',' | ';' | ':' => true,
+                    '>' => closes_generics,
+                    '<' | '!' => prev_ident,
+                    '.' | '?' => prev_operand,
+                    _ => false,
+                };
+                if !glue_next && !glue_before {
+                    out.push(' ');
+                }
+                out.push(c);
+                if opens_generics {
+                    angle_depth += 1;
+                } else if closes_generics {
+                    angle_depth -= 1;
+                }
+                glue_next = punct.spacing() == Spacing::Joint
+                    || opens_generics
+                    || matches!(c, '&' | '\'' | '!' | '.')
+                    || (matches!(c, '*' | '-') && !prev_operand)
+                    || (c == ':' && out.ends_with("::"));
+                prev_ident = false;
+                prev_operand = false;
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace if group.stream().is_empty() => ("{", "}"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::None => ("", ""),
+                };
+                let glue_before = prev_ident && group.delimiter() != Delimiter::Brace;
+                if !glue_next && !glue_before {
+                    out.push(' ');
+                }
+                out.push_str(open);
+                write_tokens(group.stream(), out);
+                out.push_str(close);
+                glue_next = false;
+                prev_ident = false;
+                prev_operand = true;
+            }
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if !glue_next {
+                    out.push(' ');
+                }
+                out.push_str(&token.to_string());
+                glue_next = false;
+                prev_ident = matches!(token, TokenTree::Ident(_));
+                prev_operand = true;
+            }
+        }
+    }
+}
+
+/// Text of `///`, `//!`, `/** */` and `#[doc = "..."]` attributes, one line per
+/// attribute, with the single space after `///` removed. `None` when undocumented.
+pub fn docs_of(attrs: &[syn::Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(meta) = &attr.meta {
+            if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }) = &meta.value {
+                let text = text.value();
+                lines.extend(text.split('\n').map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end().to_string()));
+            }
+        }
+    }
+
+    let docs = lines.join("\n").trim().to_string();
+    if docs.is_empty() { None } else { Some(docs) }
+}
+
+pub fn visibility_of(vis: &syn::Visibility) -> Visibility {
+    match vis {
+        syn::Visibility::Public(_) => Visibility::Public,
+        syn::Visibility::Restricted(restricted) if restricted.path.is_ident("crate") => Visibility::Crate,
+        syn::Visibility::Restricted(restricted) => Visibility::Restricted(tokens_to_string(&restricted.path)),
+        syn::Visibility::Inherited => Visibility::Inherited,
+    }
+}
+
+/// Attributes other than docs and derives, rendered without `#[..]`: `cfg(test)`,
+/// `deprecated(note = "use bar")`, `non_exhaustive`.
+pub fn attributes_of(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs.iter()
+        .filter(|attr| !attr.path().is_ident("doc") && !attr.path().is_ident("derive"))
+        .map(|attr| tokens_to_string(&attr.meta))
+        .collect()
+}
+
+/// Paths listed in every `#[derive(..)]`, in order.
+pub fn derives_of(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut derives = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let parsed = attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated);
+        if let Ok(paths) = parsed {
+            derives.extend(paths.iter().map(tokens_to_string));
+        }
+    }
+    derives
+}
+
+/// Source span of a syntax node. Leading outer attributes (doc comments included)
+/// are skipped so the span starts at the item itself, where an editor should jump.
+pub fn span_of<T: ToTokens>(node: &T) -> Span {
+    let mut tokens = node.to_token_stream().into_iter().peekable();
+    let mut first = None;
+    let mut last = None;
+    while let Some(token) = tokens.next() {
+        if first.is_none() {
+            if let TokenTree::Punct(punct) = &token {
+                if punct.as_char() == '#' {
+                    if let Some(TokenTree::Group(group)) = tokens.peek() {
+                        if group.delimiter() == Delimiter::Bracket {
+                            tokens.next();
+                            continue;
+                        }
+                    }
+                }
             }
+            first = Some(token.span());
         }
+        last = Some(token.span());
+    }
 
-        if let Some(for_type) = for_type {
-            self.impls.push(ImplSnapshot { for_type, trait_name, methods });
+    match (first, last) {
+        (Some(first), Some(last)) => {
+            let start = first.start();
+            let end = last.end();
+            Span {
+                start_line: start.line,
+                start_column: start.column + 1,
+                end_line: end.line,
+                end_column: end.column + 1,
+            }
         }
+        _ => Span::default(),
     }
 }
 
@@ -115,7 +836,368 @@ mod tests {
         assert_eq!(visitor.functions.len(), 1);
         assert_eq!(visitor.functions[0].name, "my_func");
         assert_eq!(visitor.functions[0].variables.len(), 1);
-        assert_eq!(visitor.functions[0].variables[0].0, "x");
+        assert_eq!(visitor.functions[0].variables[0].name, "x");
+    }
+
+    #[test]
+    fn test_spans_skip_attributes() {
+        let code = "/// Docs\n#[inline]\nfn spanned() {\n    let value = 1;\n}\n\nstruct Later;\n";
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let func = &visitor.functions[0];
+        assert_eq!((func.span.start_line, func.span.start_column), (3, 1));
+        assert_eq!((func.span.end_line, func.span.end_column), (5, 2));
+        assert_eq!(func.variables[0].span.start_line, 4);
+        assert_eq!(func.variables[0].span.start_column, 9);
+        assert_eq!(visitor.structs[0].span.start_line, 7);
+    }
+
+    #[test]
+    fn test_nested_variable_extraction() {
+        let code = r#"
+            fn nested(items: Vec<Option<(u8, u8)>>) {
+                let (mut total, count): (u32, usize) = (0, items.len());
+                for (index, item) in items.iter().enumerate() {
+                    if let Some((left, _)) = item {
+                        let Point { x, y: renamed } = point();
+                        total += *left as u32;
+                    }
+                }
+                match items.first() {
+                    Some(Some(pair @ (a, _))) => {}
+                    None | Some(None) => {}
+                }
+                let add = |value: u32, [first, .., last]: [u8; 3]| value + 1;
+                fn inner() { let hidden = 1; }
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let vars: Vec<(&str, usize, bool)> = visitor.functions[0].variables.iter()
+            .map(|v| (v.name.as_str(), v.scope_depth, v.mutable))
+            .collect();
+        assert_eq!(vars, vec![
+            ("total", 0, true),
+            ("count", 0, false),
+            ("index", 1, false),
+            ("item", 1, false),
+            ("left", 2, false),
+            ("x", 2, false),
+            ("renamed", 2, false),
+            ("pair", 1, false),
+            ("a", 1, false),
+            ("add", 0, false),
+            ("value", 1, false),
+            ("first", 1, false),
+            ("last", 1, false),
+        ]);
+        assert_eq!(visitor.functions[0].variables[0].ty.as_deref(), Some("u32"));
+        assert_eq!(visitor.functions[0].variables[1].ty.as_deref(), Some("usize"));
+        assert_eq!(visitor.functions[0].variables[10].ty.as_deref(), Some("u32"));
+    }
+
+    #[test]
+    fn test_type_paths() {
+        assert_eq!(type_paths("&'a mut HashMap<String, Vec<crate::graph::Node>>"), vec!["HashMap", "String", "Vec", "crate::graph::Node"]);
+        assert_eq!(type_paths("Box<dyn Fn(Config) -> Result<(), Error> + Send>"), vec!["Box", "Fn", "Config", "Result", "Error", "Send"]);
+        assert_eq!(type_paths("[Option<Self>; 4]"), vec!["Option", "Self"]);
+        assert!(type_paths("not a type ->").is_empty());
+    }
+
+    #[test]
+    fn test_call_extraction() {
+        let code = r#"
+            fn run(&self, engine: Engine) {
+                let graph = Self::build::<u8>(engine.scan(), ::std::mem::take(&mut x));
+                self.cache.save().unwrap();
+                items.iter().for_each(|item| (helper)(item));
+                println!("{}", format_name(graph));
+                fn inner() { hidden(); }
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let calls: Vec<(&str, Option<&str>, bool)> = visitor.functions[0].calls.iter()
+            .map(|c| (c.callee.as_str(), c.receiver.as_deref(), c.is_method))
+            .collect();
+        assert_eq!(calls, vec![
+            ("Self::build", None, false),
+            ("scan", Some("engine"), true),
+            ("::std::mem::take", None, false),
+            ("unwrap", None, true),
+            ("save", Some("self.cache"), true),
+            ("for_each", None, true),
+            ("iter", Some("items"), true),
+            ("format_name", None, false),
+        ]);
+        assert_eq!(visitor.functions[0].calls[1].span.start_line, 3);
+    }
+
+    #[test]
+    fn test_other_item_kinds() {
+        let code = r#"
+            use std::collections::{self, HashMap as Map, hash_map::*};
+            use ::serde::Serialize;
+            const MAX_SIZE: usize = 10;
+            static mut COUNTER: u32 = 0;
+            type Result<T> = std::result::Result<T, String>;
+            union Bits { int: u32, float: f32 }
+            macro_rules! square { ($x:expr) => { $x * $x }; }
+            extern "C" {
+                fn abs(input: i32) -> i32;
+                static errno: i32;
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let uses: Vec<(&str, Option<&str>, bool)> = visitor.uses.iter()
+            .map(|u| (u.path.as_str(), u.alias.as_deref(), u.is_glob))
+            .collect();
+        assert_eq!(uses, vec![
+            ("std::collections", None, false),
+            ("std::collections::HashMap", Some("Map"), false),
+            ("std::collections::hash_map", None, true),
+            ("::serde::Serialize", None, false),
+        ]);
+        assert_eq!((visitor.consts[0].name.as_str(), visitor.consts[0].ty.as_str()), ("MAX_SIZE", "usize"));
+        assert!(visitor.statics[0].mutable);
+        assert_eq!(visitor.type_aliases[0].generics, vec!["T"]);
+        assert_eq!(visitor.type_aliases[0].target, "std::result::Result<T, String>");
+        assert_eq!(visitor.unions[0].fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), vec!["int", "float"]);
+        assert_eq!(visitor.macros[0].name, "square");
+        assert_eq!(visitor.extern_blocks[0].abi.as_deref(), Some("C"));
+        assert_eq!(visitor.extern_blocks[0].functions[0].signature_string(), "fn abs(input: i32) -> i32");
+        assert_eq!(visitor.extern_blocks[0].statics[0].name, "errno");
+    }
+
+    #[test]
+    fn test_impl_self_type_normalization() {
+        let code = r#"
+            impl<T: Clone> Wrapper<T> { fn get(&self) -> T { todo!() } }
+            impl crate::engine::TraversalEngine {}
+            impl<'a> std::convert::From<&'a str> for &'a mut Token<'a> {}
+            impl<T> Summary for [T] {}
+            impl Summary for (u8, String) {}
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        assert_eq!(visitor.impls.len(), 5);
+        let wrapper = &visitor.impls[0];
+        assert_eq!(wrapper.for_type, "Wrapper");
+        assert_eq!(wrapper.type_args, vec!["T"]);
+        assert_eq!(wrapper.generics, vec!["T: Clone"]);
+        assert_eq!(wrapper.methods[0].owner.as_deref(), Some("Wrapper"));
+
+        assert_eq!(visitor.impls[1].for_type, "TraversalEngine");
+
+        let token = &visitor.impls[2];
+        assert_eq!(token.for_type, "Token");
+        assert_eq!(token.self_ty, "&'a mut Token<'a>");
+        assert_eq!(token.trait_name.as_deref(), Some("From"));
+        assert_eq!(token.trait_args, vec!["&'a str"]);
+        assert_eq!(token.header(), "impl<'a> std::convert::From<&'a str> for &'a mut Token<'a>");
+
+        assert_eq!(visitor.impls[3].for_type, "[T]");
+        assert_eq!(visitor.impls[4].for_type, "(u8, String)");
+
+        assert_eq!(visitor.impls[1].self_path.as_deref(), Some("crate::engine::TraversalEngine"));
+        assert_eq!(token.self_path.as_deref(), Some("Token"));
+        assert_eq!(visitor.impls[3].self_path, None);
+        assert_eq!(visitor.impls[4].self_path, None);
+    }
+
+    #[test]
+    fn test_type_path_of_args_variables_and_fields() {
+        let code = r#"
+            struct Holder { inner: Box<crate::a::Node>, pair: (u8, Node) }
+            fn run(a: &'a mut crate::a::Foo<T>, b: (u8, Foo), c: &dyn Bar, d: [Foo; 2], e: <T as Tr>::Out, f: *const (Self)) {
+                let g: Vec<Foo> = Vec::new();
+                let h = 1;
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let paths: Vec<Option<&str>> = visitor.functions[0].signature.inputs.iter().map(|arg| arg.type_path.as_deref()).collect();
+        assert_eq!(paths, vec![Some("crate::a::Foo"), None, None, None, None, Some("Self")]);
+        let variables: Vec<Option<&str>> = visitor.functions[0].variables.iter().map(|var| var.type_path.as_deref()).collect();
+        assert_eq!(variables, vec![Some("Vec"), None]);
+        let fields: Vec<Option<&str>> = visitor.structs[0].fields.iter().map(|field| field.type_path.as_deref()).collect();
+        assert_eq!(fields, vec![Some("Box"), None]);
+    }
+
+    #[test]
+    fn test_doc_extraction() {
+        let code = r#"
+            //! Crate docs.
+            //! Second line.
+
+            /// Adds numbers.
+            ///
+            /// Longer description.
+            fn add() {}
+
+            #[doc = "Attribute docs"]
+            struct Point {
+                /// Horizontal.
+                x: i32,
+            }
+
+            enum Shape {
+                /** Round */
+                Circle,
+            }
+
+            /// Inline module.
+            mod inner {
+                //! Inner docs.
+            }
+
+            #[doc(hidden)]
+            fn hidden() {}
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        assert_eq!(visitor.docs.as_deref(), Some("Crate docs.\nSecond line."));
+        assert_eq!(visitor.functions[0].docs.as_deref(), Some("Adds numbers.\n\nLonger description."));
+        assert_eq!(visitor.structs[0].docs.as_deref(), Some("Attribute docs"));
+        assert_eq!(visitor.structs[0].fields[0].docs.as_deref(), Some("Horizontal."));
+        assert_eq!(visitor.enums[0].variants[0].docs.as_deref(), Some("Round"));
+        assert_eq!(visitor.modules[0].docs.as_deref(), Some("Inline module.\nInner docs."));
+        assert!(visitor.functions[1].docs.is_none());
+    }
+
+    #[test]
+    fn test_visibility_attributes_and_derives() {
+        let code = r#"
+            #[derive(Debug, Clone, serde::Serialize)]
+            #[non_exhaustive]
+            pub struct Config {
+                pub(crate) name: String,
+                #[serde(skip)]
+                cache: Vec<u8>,
+            }
+
+            #[derive(PartialEq)]
+            #[derive(Eq)]
+            pub(super) enum Mode { #[default] Fast }
+
+            /// Documented.
+            #[test]
+            #[cfg(feature = "slow")]
+            fn slow_test() {}
+
+            #[deprecated(note = "use bar")]
+            pub(in crate::a) fn foo() {}
+
+            pub use inner::Thing;
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let config = &visitor.structs[0];
+        assert_eq!(config.visibility, Visibility::Public);
+        assert_eq!(config.derives, vec!["Debug", "Clone", "serde::Serialize"]);
+        assert_eq!(config.attributes, vec!["non_exhaustive"]);
+        assert_eq!(config.fields[0].visibility, Visibility::Crate);
+        assert_eq!(config.fields[1].visibility, Visibility::Inherited);
+        assert_eq!(config.fields[1].attributes, vec!["serde(skip)"]);
+
+        let mode = &visitor.enums[0];
+        assert_eq!(mode.visibility, Visibility::Restricted("super".to_string()));
+        assert_eq!(mode.derives, vec!["PartialEq", "Eq"]);
+        assert_eq!(mode.variants[0].attributes, vec!["default"]);
+
+        assert_eq!(visitor.functions[0].visibility, Visibility::Inherited);
+        assert_eq!(visitor.functions[0].attributes, vec!["test", "cfg(feature = \"slow\")"]);
+        assert_eq!(visitor.functions[1].visibility, Visibility::Restricted("crate::a".to_string()));
+        assert_eq!(visitor.functions[1].visibility.to_string(), "pub(in crate::a)");
+        assert_eq!(visitor.functions[1].attributes, vec!["deprecated(note = \"use bar\")"]);
+        assert_eq!(visitor.uses[0].visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn test_method_extraction() {
+        let code = r#"
+            trait Shape {
+                fn area(&self) -> f64;
+                fn describe(&self) -> String {
+                    let label = "shape";
+                    label.to_string()
+                }
+            }
+            struct Circle { radius: f64 }
+            impl Shape for Circle {
+                fn area(&self) -> f64 {
+                    let squared = self.radius * self.radius;
+                    squared * 3.14
+                }
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let area = &visitor.impls[0].methods[0];
+        assert_eq!(area.name, "area");
+        assert_eq!(area.owner.as_deref(), Some("Circle"));
+        assert_eq!(area.trait_name.as_deref(), Some("Shape"));
+        assert_eq!(area.signature.receiver.as_deref(), Some("&self"));
+        assert_eq!(area.variables[0].name, "squared");
+        assert_eq!(area.qualified_name(), "Circle::area");
+
+        let describe = &visitor.traits[0].methods[1];
+        assert_eq!(describe.trait_name.as_deref(), Some("Shape"));
+        assert_eq!(describe.variables[0].name, "label");
+        assert!(visitor.traits[0].methods[0].variables.is_empty());
+        assert!(visitor.functions.is_empty());
+    }
+
+    #[test]
+    fn test_signature_extraction() {
+        let code = r#"
+            const extern "C" fn raw(ptr: *const u8) {}
+            extern fn bare() -> ! { loop {} }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let raw = &visitor.functions[0];
+        assert!(raw.signature.is_const);
+        assert_eq!(raw.signature.abi.as_deref(), Some("C"));
+        assert_eq!(raw.signature.inputs[0].ty, "*const u8");
+        assert_eq!(raw.signature_string(), "const extern \"C\" fn raw(ptr: *const u8)");
+
+        let bare = &visitor.functions[1];
+        assert_eq!(bare.signature.abi.as_deref(), Some("C"));
+        assert_eq!(bare.signature.output.as_deref(), Some("!"));
+
+        let sig = signature_of(&syn::parse_str::<syn::ImplItemFn>(
+            "pub async unsafe fn fetch<'a, T: Clone + 'a>(&'a mut self, (id, _): (u64, u8), keys: &[&'a str]) -> Option<Vec<T>> where T: Send { None }"
+        ).unwrap().sig);
+        assert!(sig.is_async && sig.is_unsafe && !sig.is_const);
+        assert_eq!(sig.generics, vec!["'a", "T: Clone + 'a"]);
+        assert_eq!(sig.receiver.as_deref(), Some("&'a mut self"));
+        assert_eq!(sig.inputs[0].pattern, "(id, _)");
+        assert_eq!(sig.inputs[0].ty, "(u64, u8)");
+        assert_eq!(sig.inputs[1].ty, "&[&'a str]");
+        assert_eq!(sig.output.as_deref(), Some("Option<Vec<T>>"));
+        assert_eq!(sig.where_clause.as_deref(), Some("where T: Send"));
     }
 
     #[test]
@@ -133,7 +1215,40 @@ mod tests {
         assert_eq!(visitor.structs.len(), 1);
         assert_eq!(visitor.structs[0].name, "MyStruct");
         assert_eq!(visitor.structs[0].fields.len(), 2);
-        assert_eq!(visitor.structs[0].fields[0], "field1");
-        assert_eq!(visitor.structs[0].fields[1], "field2");
+        assert_eq!(visitor.structs[0].fields[0].name, "field1");
+        assert_eq!(visitor.structs[0].fields[1].name, "field2");
+        assert_eq!(visitor.structs[0].fields[1].ty, "String");
+    }
+
+    #[test]
+    fn test_tuple_structs_and_variant_payloads() {
+        let code = r#"
+            struct Meters(pub f64, u8);
+            struct Marker;
+            enum Event {
+                Quit,
+                Key(char),
+                Move { x: i32, y: i32 },
+                Code = 1 << 3,
+            }
+        "#;
+        let file = parse_file(code).unwrap();
+        let mut visitor = SnapshotVisitor::default();
+        visitor.visit_file(&file);
+
+        let meters = &visitor.structs[0];
+        assert_eq!(meters.kind, StructKind::Tuple);
+        assert_eq!(meters.fields.iter().map(|f| (f.name.as_str(), f.ty.as_str())).collect::<Vec<_>>(), vec![("0", "f64"), ("1", "u8")]);
+        assert!(meters.fields[0].is_positional());
+        assert_eq!(visitor.structs[1].kind, StructKind::Unit);
+        assert!(visitor.structs[1].fields.is_empty());
+
+        let variants = &visitor.enums[0].variants;
+        assert_eq!(variants[0].kind, StructKind::Unit);
+        assert_eq!(variants[1].kind, StructKind::Tuple);
+        assert_eq!(variants[1].fields[0].ty, "char");
+        assert_eq!(variants[2].kind, StructKind::Named);
+        assert_eq!(variants[2].fields[1].name, "y");
+        assert_eq!(variants[3].discriminant.as_deref(), Some("1 << 3"));
     }
 }
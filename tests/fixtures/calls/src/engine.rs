@@ -0,0 +1,20 @@
+use crate::graph::{self, Graph};
+
+pub struct Engine {
+    graph: Graph,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Engine { graph: graph::build() }
+    }
+
+    pub fn step(&mut self) -> usize {
+        self.graph.size() + self.reset()
+    }
+
+    fn reset(&mut self) -> usize {
+        let fresh = Self::new();
+        Some(fresh).map(|engine| engine.graph.size()).unwrap_or_default()
+    }
+}
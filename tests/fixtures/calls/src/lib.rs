@@ -0,0 +1,18 @@
+mod graph;
+pub mod engine;
+
+use engine::Engine;
+
+pub fn run(engine: &mut Engine) -> usize {
+    let total = engine.step();
+    helper(total);
+    graph::build();
+    total
+}
+
+fn helper(count: usize) {
+    let log = |value: usize| value;
+    log(count);
+    println!("{}", graph::describe(count));
+    std::process::exit(0);
+}
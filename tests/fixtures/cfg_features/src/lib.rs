@@ -0,0 +1,16 @@
+#[cfg(feature = "extra")]
+mod extra;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(feature = "std")]
+pub fn run() {}
+
+#[cfg(not(feature = "std"))]
+pub fn run() {}
+
+#[cfg_attr(feature = "extra", derive(Debug))]
+pub struct Settings {
+    #[cfg(feature = "extra")]
+    pub verbose: bool,
+}
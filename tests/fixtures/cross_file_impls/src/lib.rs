@@ -0,0 +1,3 @@
+pub mod model;
+pub mod model_impls;
+pub mod shapes;
@@ -0,0 +1,8 @@
+pub struct Account {
+    pub id: u64,
+}
+
+pub enum State {
+    Open,
+    Closed,
+}
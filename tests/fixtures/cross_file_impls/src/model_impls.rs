@@ -0,0 +1,13 @@
+use crate::model::{Account, State};
+
+impl Account {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "state")
+    }
+}
@@ -0,0 +1,5 @@
+pub struct Circle;
+
+impl super::Account {
+    pub fn sibling(&self) {}
+}
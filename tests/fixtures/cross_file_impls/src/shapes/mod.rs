@@ -0,0 +1,7 @@
+pub mod circle;
+
+pub struct Account;
+
+impl Account {
+    pub fn local(&self) {}
+}
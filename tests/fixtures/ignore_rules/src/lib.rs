@@ -0,0 +1,6 @@
+mod contest;
+mod test;
+#[path = "bindings.gen.rs"]
+mod bindings;
+#[path = "keep.gen.rs"]
+mod keep;
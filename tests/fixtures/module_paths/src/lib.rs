@@ -0,0 +1,12 @@
+mod net;
+mod legacy;
+
+/// Formatting helpers.
+pub mod util {
+    pub mod fmt;
+
+    pub fn identity() {}
+}
+
+#[path = "platform/unix_impl.rs"]
+mod platform;
@@ -0,0 +1 @@
+pub fn page_size() {}
@@ -0,0 +1,5 @@
+mod net;
+
+struct Server;
+
+fn main() {}
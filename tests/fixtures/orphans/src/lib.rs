@@ -0,0 +1,7 @@
+mod used;
+#[cfg(feature = "gated")]
+mod gated;
+#[cfg(windows)]
+mod platform {
+    mod win;
+}
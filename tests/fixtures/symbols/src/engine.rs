@@ -0,0 +1,30 @@
+use crate::graph::{Graph, Node};
+use std::collections::HashMap;
+use super::missing::Thing;
+
+pub struct Engine {
+    graph: Graph,
+    nodes: HashMap<String, Node>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Engine { graph: Graph, nodes: HashMap::new() }
+    }
+
+    fn clear(&mut self) {
+        self.nodes.clear();
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+mod tests {
+    use super::*;
+
+    fn check() {}
+}
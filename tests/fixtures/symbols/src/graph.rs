@@ -0,0 +1,10 @@
+pub struct Graph;
+
+pub struct Node;
+
+pub enum Kind {
+    Lib,
+    Bin,
+}
+
+fn private() {}
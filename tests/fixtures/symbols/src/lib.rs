@@ -0,0 +1,15 @@
+mod engine;
+pub mod graph;
+
+pub use engine::Engine;
+pub use graph::*;
+
+pub mod prelude {
+    pub use crate::engine::{self, Engine as Runner};
+    pub use super::graph::Kind::*;
+}
+
+#[macro_export]
+macro_rules! node {
+    () => {};
+}
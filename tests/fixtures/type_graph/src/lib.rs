@@ -0,0 +1,37 @@
+mod model;
+
+use model::{Config, Node};
+
+pub struct Engine {
+    config: Config,
+    nodes: Vec<Node>,
+    parent: Option<Box<Engine>>,
+}
+
+pub enum Event {
+    Added(Node),
+    Reset { config: Config },
+    Cleared,
+}
+
+pub type NodeMap = std::collections::HashMap<String, Node>;
+
+impl Engine {
+    pub fn new(config: Config) -> Self {
+        Engine { config, nodes: Vec::new(), parent: None }
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        Vec::new()
+    }
+}
+
+impl From<Config> for Engine {
+    fn from(config: Config) -> Self {
+        Engine::new(config)
+    }
+}
+
+pub trait Visitor {
+    fn visit(&mut self, node: &Node);
+}
@@ -0,0 +1,11 @@
+pub struct Config {
+    pub name: String,
+}
+
+pub struct Node {
+    pub id: u32,
+}
+
+impl super::Visitor for Node {
+    fn visit(&mut self, _node: &Node) {}
+}
@@ -0,0 +1,5 @@
+pub use core::core as run;
+
+pub fn cli() {
+    core::core();
+}
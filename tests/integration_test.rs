@@ -1,7 +1,11 @@
-use furnace::engine::TraversalEngine;
+use furnace::engine::{TraversalEngine, ScanOptions, LayoutSource};
+use furnace::graph::TargetKind;
+use furnace::cfg::FeatureSelection;
 use furnace::config::load_config;
+use furnace::error::ScanError;
 use furnace::linting::lint_snapshots;
 use std::path::PathBuf;
+use std::fs;
 
 #[test]
 fn test_engine_scans_furnace_project() {
@@ -17,7 +21,7 @@ fn test_engine_scans_furnace_project() {
     assert_eq!(graph.crates[0].name, "furnace");
     
     // Should have a root module
-    assert_eq!(graph.crates[0].root_module.name, "crate");
+    assert_eq!(graph.crates[0].root_module().name, "crate");
 }
 
 #[test]
@@ -26,7 +30,7 @@ fn test_engine_discovers_modules() {
     let engine = TraversalEngine::new(project_path);
     let graph = engine.scan();
     
-    let root_module = &graph.crates[0].root_module;
+    let root_module = graph.crates[0].root_module();
     
     // Should discover submodules
     assert!(!root_module.submodules.is_empty(), "Should find submodules");
@@ -59,7 +63,7 @@ fn test_engine_ignores_target() {
     
     let mut all_paths = Vec::new();
     for crate_node in &graph.crates {
-        collect_paths(&crate_node.root_module, &mut all_paths);
+        collect_paths(crate_node.root_module(), &mut all_paths);
     }
     
     // No path should contain "target/"
@@ -107,7 +111,7 @@ fn test_linting_integration() {
     
     let mut snapshots = Vec::new();
     for crate_node in &graph.crates {
-        collect_snapshots(&crate_node.root_module, &mut snapshots, &config.ignore);
+        collect_snapshots(crate_node.root_module(), &mut snapshots, &config.ignore);
     }
     
     // Run linting
@@ -124,7 +128,7 @@ fn test_file_hashing() {
     let graph = engine.scan();
     
     // Check that files have hashes
-    if let Some(file) = &graph.crates[0].root_module.file {
+    if let Some(file) = &graph.crates[0].root_module().file {
         assert!(!file.hash.is_empty(), "File should have a hash");
         assert_eq!(file.hash.len(), 64, "SHA256 hash should be 64 hex characters");
     }
@@ -149,7 +153,7 @@ fn test_snapshots_contain_data() {
     }
     
     for crate_node in &graph.crates {
-        if check_snapshots(&crate_node.root_module) {
+        if check_snapshots(crate_node.root_module()) {
             found_snapshot = true;
             break;
         }
@@ -157,3 +161,526 @@ fn test_snapshots_contain_data() {
     
     assert!(found_snapshot, "Should find at least one snapshot with parsed content");
 }
+
+fn find_snapshot<'a>(module: &'a furnace::graph::ModuleNode, suffix: &str) -> Option<&'a furnace::types::RustFileSnapshot> {
+    if let Some(snapshot) = module.file.as_ref().and_then(|f| f.snapshot.as_ref()) {
+        if snapshot.path.ends_with(suffix) {
+            return Some(snapshot);
+        }
+    }
+    module.submodules.iter().find_map(|m| find_snapshot(m, suffix))
+}
+
+#[test]
+fn test_impls_link_across_files() {
+    let engine = TraversalEngine::new(PathBuf::from("tests/fixtures/cross_file_impls"));
+    let graph = engine.scan();
+    let root = graph.crates[0].root_module();
+
+    let model = find_snapshot(root, "src/model.rs").unwrap();
+    let account = &model.structs[0];
+    assert_eq!(account.methods, vec!["new"]);
+    assert_eq!(account.impls.len(), 1);
+    assert!(account.impls[0].location.path.ends_with("src/model_impls.rs"));
+    assert_eq!(account.impls[0].location.span.start_line, 3);
+
+    let state = &model.enums[0];
+    assert_eq!(state.methods, vec!["fmt"]);
+    assert_eq!(state.impls[0].trait_name.as_deref(), Some("Display"));
+
+    // `shapes::Account` shadows the crate-level one for impls in its own directory
+    let shapes = find_snapshot(root, "src/shapes/mod.rs").unwrap();
+    assert_eq!(shapes.structs[0].methods, vec!["local", "sibling"]);
+
+    let impls = find_snapshot(root, "src/model_impls.rs").unwrap();
+    let target = impls.impls[0].target.as_ref().unwrap();
+    assert!(target.to_string().ends_with("src/model.rs:1"));
+}
+
+#[test]
+fn test_cfg_evaluation_follows_feature_selection() {
+    let fixture = PathBuf::from("tests/fixtures/cfg_features");
+    let module_names = |graph: &furnace::graph::ProjectGraph| -> Vec<String> {
+        graph.crates[0].root_module().submodules.iter().map(|m| m.name.clone()).collect()
+    };
+
+    // Default features: `std` on, `extra` off, target undecided
+    let graph = TraversalEngine::new(fixture.clone()).scan();
+    let lib = find_snapshot(graph.crates[0].root_module(), "src/lib.rs").unwrap();
+    assert_eq!(lib.functions.len(), 1);
+    assert_eq!(lib.functions[0].attributes, vec!["cfg(feature = \"std\")"]);
+    assert!(lib.structs[0].fields.is_empty());
+    assert!(lib.structs[0].derives.is_empty());
+    assert_eq!(module_names(&graph), vec!["windows"]);
+
+    let options = ScanOptions {
+        features: FeatureSelection { features: vec!["extra".to_string()], no_default_features: true, ..FeatureSelection::default() },
+        target_os: Some("linux".to_string()),
+        ..ScanOptions::default()
+    };
+    let graph = TraversalEngine::with_options(fixture, options).scan();
+    let lib = find_snapshot(graph.crates[0].root_module(), "src/lib.rs").unwrap();
+    assert_eq!(lib.functions[0].attributes, vec!["cfg(not(feature = \"std\"))"]);
+    assert_eq!(lib.structs[0].fields.len(), 1);
+    assert_eq!(lib.structs[0].derives, vec!["Debug"]);
+    assert_eq!(module_names(&graph), vec!["extra"]);
+}
+
+#[test]
+fn test_module_resolution_matches_rustc() {
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/module_paths")).scan();
+    let root = graph.crates[0].root_module();
+    let child = |module: &furnace::graph::ModuleNode, name: &str| -> furnace::graph::ModuleNode {
+        module.submodules.iter().find(|m| m.name == name).cloned()
+            .unwrap_or_else(|| panic!("module {} not found under {}", name, module.name))
+    };
+    let file_of = |module: &furnace::graph::ModuleNode| module.file.as_ref().map(|f| f.path.clone()).unwrap();
+
+    // `net.rs` keeps its children in `net/`
+    let tcp = child(&child(root, "net"), "tcp");
+    assert!(file_of(&tcp).ends_with("src/net/tcp.rs"));
+
+    let compat = child(&child(root, "legacy"), "compat");
+    assert!(file_of(&compat).ends_with("src/legacy/compat.rs"));
+
+    // Inline modules are nodes of their own, without a file
+    let util = child(root, "util");
+    assert!(util.file.is_none());
+    assert_eq!(util.docs.as_deref(), Some("Formatting helpers."));
+    assert!(file_of(&child(&util, "fmt")).ends_with("src/util/fmt.rs"));
+
+    let platform = child(root, "platform");
+    assert!(file_of(&platform).ends_with("src/platform/unix_impl.rs"));
+    assert!(file_of(&child(&platform, "helper")).ends_with("src/platform/helper.rs"));
+
+    // The inline module's items stay in lib.rs's snapshot
+    let lib = find_snapshot(root, "src/lib.rs").unwrap();
+    assert_eq!(lib.functions[0].name, "identity");
+}
+
+#[test]
+fn test_every_cargo_target_is_scanned() {
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/targets")).scan();
+    let krate = &graph.crates[0];
+    let targets: Vec<(&str, TargetKind)> = krate.targets.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+    assert_eq!(targets, vec![
+        ("targets_demo", TargetKind::Lib),
+        ("extra", TargetKind::Bin),
+        ("multi", TargetKind::Bin),
+        ("targets-demo", TargetKind::Bin),
+        ("tool", TargetKind::Bin),
+        ("demo", TargetKind::Example),
+        ("smoke", TargetKind::Test),
+        ("speed", TargetKind::Bench),
+        ("build-script-build", TargetKind::BuildScript),
+    ]);
+
+    // A custom `[lib] path` is the primary root
+    assert!(krate.root_module().file.as_ref().unwrap().path.ends_with("src/core.rs"));
+
+    let multi = krate.targets.iter().find(|t| t.name == "multi").unwrap();
+    let helper = multi.root_module.submodules[0].file.as_ref().unwrap();
+    assert!(helper.path.ends_with("src/bin/multi/helper.rs"));
+}
+
+#[test]
+fn test_workspace_members_follow_cargo_rules() {
+    let fixture = PathBuf::from("tests/fixtures/workspace");
+    let graph = TraversalEngine::new(fixture.clone()).scan();
+    let crates: Vec<(&str, bool)> = graph.crates.iter().map(|c| (c.name.as_str(), c.default_member)).collect();
+    assert_eq!(crates, vec![
+        ("ws-root", false),
+        ("ws-core", true),
+        ("ws-util", false),
+        ("ws-cli", true),
+    ]);
+
+    let options = ScanOptions { default_members_only: true, ..ScanOptions::default() };
+    let graph = TraversalEngine::with_options(fixture, options).scan();
+    let names: Vec<&str> = graph.crates.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["ws-core", "ws-cli"]);
+}
+
+#[test]
+fn test_directory_fallback_without_manifest() {
+    use furnace::graph::{ModuleNode, TargetKind};
+
+    fn names(module: &ModuleNode) -> Vec<&str> {
+        module.submodules.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/no_manifest")).scan();
+    let crates: Vec<&str> = graph.crates.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(crates, vec!["proj", "scripts"]);
+
+    // `proj/src` is named after its parent and rooted at main.rs
+    let proj = &graph.crates[0];
+    assert_eq!(proj.targets[0].kind, TargetKind::Bin);
+    assert!(proj.root_module().file.as_ref().unwrap().path.ends_with("proj/src/main.rs"));
+    assert_eq!(names(proj.root_module()), vec!["net"]);
+    assert_eq!(names(&proj.root_module().submodules[0]), vec!["tcp"]);
+    let main = find_snapshot(proj.root_module(), "src/main.rs").unwrap();
+    assert_eq!(main.structs[0].methods, vec!["listen"]);
+
+    // A loose folder of scripts has no root file; every file is a module
+    let scripts = graph.crates[1].root_module();
+    assert!(scripts.file.is_none());
+    assert_eq!(names(scripts), vec!["hello", "util"]);
+    assert_eq!(names(&scripts.submodules[1]), vec!["fmt"]);
+    assert_eq!(scripts.submodules[1].docs.as_deref(), Some("Shared helpers."));
+}
+
+#[test]
+fn test_unreached_files_are_orphans() {
+    let fixture = PathBuf::from("tests/fixtures/orphans");
+    let orphan_names = |graph: &furnace::graph::ProjectGraph| -> Vec<String> {
+        graph.crates[0].orphans.iter()
+            .map(|file| file.path.strip_prefix(&fixture).unwrap().to_string_lossy().to_string())
+            .collect()
+    };
+
+    // `cli.rs` is reached from the bin target only; cfg'd-out modules are not orphans
+    let graph = TraversalEngine::new(fixture.clone()).scan();
+    assert_eq!(orphan_names(&graph), vec!["src/dead.rs", "src/stale/mod.rs"]);
+    assert!(graph.crates[0].orphans[0].snapshot.as_ref().is_some_and(|snapshot| snapshot.functions[0].name == "forgotten"));
+
+    let mut config = furnace::config::LintConfig::default();
+    assert!(furnace::linting::lint_orphans(&graph.crates[0].orphans, &config).is_empty());
+    config.style.warn_orphan_files = Some(true);
+    let warnings = furnace::linting::lint_orphans(&graph.crates[0].orphans, &config);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("dead.rs' is not reachable from any module tree"));
+
+    let options = ScanOptions {
+        features: FeatureSelection { features: vec!["gated".to_string()], ..FeatureSelection::default() },
+        ..ScanOptions::default()
+    };
+    let graph = TraversalEngine::with_options(fixture.clone(), options).scan();
+    assert_eq!(orphan_names(&graph), vec!["src/dead.rs", "src/stale/mod.rs"]);
+}
+
+#[test]
+fn test_snapshot_cache_reuses_unchanged_files() {
+    let root = std::env::temp_dir().join(format!("furnace-cache-scan-{}", std::process::id()));
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]\nname = \"cached\"\nversion = \"0.1.0\"\n\n[features]\nx = []\n").unwrap();
+    fs::write(root.join("src/lib.rs"), "/// Docs.\nmod a;\n#[cfg(feature = \"x\")]\nmod b;\n").unwrap();
+    fs::write(root.join("src/a.rs"), "pub fn first() {}\n").unwrap();
+    fs::write(root.join("src/b.rs"), "pub fn gated() {}\n").unwrap();
+
+    let options = ScanOptions { cache: true, cache_dir: Some(root.join("cache")), ..ScanOptions::default() };
+    let scan = || TraversalEngine::with_options(root.clone(), options.clone()).scan();
+    let functions_in_a = |graph: &furnace::graph::ProjectGraph| -> Vec<String> {
+        let module = &graph.crates[0].root_module().submodules[0];
+        module.file.as_ref().unwrap().snapshot.as_ref().unwrap().functions.iter().map(|f| f.name.clone()).collect()
+    };
+
+    let fresh = scan();
+    assert!(root.join("cache/snapshots.bin").exists());
+
+    // The module tree and docs survive a round trip through the cache
+    let cached = scan();
+    let submodules = &cached.crates[0].root_module().submodules;
+    assert_eq!(submodules.len(), 1);
+    assert_eq!(submodules[0].docs.as_deref(), Some("Docs."));
+    assert_eq!(functions_in_a(&cached), functions_in_a(&fresh));
+    assert_eq!(cached.crates[0].orphans.len(), 0);
+
+    fs::write(root.join("src/a.rs"), "pub fn first() {}\npub fn second() {}\n").unwrap();
+    assert_eq!(functions_in_a(&scan()), vec!["first", "second"]);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_snapshot_cache_stays_out_of_plain_directories() {
+    let root = std::env::temp_dir().join(format!("furnace-cache-plain-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+
+    // No manifest means no target dir: the cache is off unless given a directory
+    let options = ScanOptions { cache: true, ..ScanOptions::default() };
+    TraversalEngine::with_options(root.clone(), options).scan();
+    assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+    let options = ScanOptions { cache: true, cache_dir: Some(root.join("cache")), ..ScanOptions::default() };
+    TraversalEngine::with_options(root.clone(), options).scan();
+    assert!(root.join("cache/snapshots.bin").exists());
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_parallel_scan_is_deterministic() {
+    // Crates and files are scanned concurrently; the graph must not depend on scheduling
+    for fixture in ["tests/fixtures/workspace", "tests/fixtures/module_paths", "tests/fixtures/no_manifest"] {
+        let first = serde_json::to_string(&TraversalEngine::new(PathBuf::from(fixture)).scan()).unwrap();
+        for _ in 0..5 {
+            let again = serde_json::to_string(&TraversalEngine::new(PathBuf::from(fixture)).scan()).unwrap();
+            assert_eq!(first, again, "{}", fixture);
+        }
+    }
+}
+
+#[test]
+fn test_ignore_rules_use_gitignore_syntax() {
+    let options = ScanOptions { ignore: vec!["test".to_string()], ..ScanOptions::default() };
+    let graph = TraversalEngine::with_options(PathBuf::from("tests/fixtures/ignore_rules"), options).scan();
+    let modules: Vec<&str> = graph.crates[0].root_module().submodules.iter().map(|m| m.name.as_str()).collect();
+
+    // `test` is a path component, not a substring; `.furnaceignore` re-includes one generated file
+    assert_eq!(modules, vec!["contest", "keep"]);
+    assert!(graph.crates[0].orphans.is_empty());
+}
+
+#[test]
+fn test_scan_errors_are_collected() {
+    let root = std::env::temp_dir().join(format!("furnace-scan-errors-{}", std::process::id()));
+    fs::create_dir_all(root.join("src/unreadable.rs")).unwrap();
+    fs::create_dir_all(root.join("bad")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]\nname = \"errors\"\nversion = \"0.1.0\"\n\n[workspace]\nmembers = [\"bad\"]\n").unwrap();
+    fs::write(root.join("src/lib.rs"), "mod broken;\nmod unreadable;\npub fn fine() {}\n").unwrap();
+    fs::write(root.join("src/broken.rs"), "pub fn ok() {}\n\npub fn broken(x) {}\n").unwrap();
+    fs::write(root.join("bad/Cargo.toml"), "[package\nname = \"bad\"\n").unwrap();
+
+    // Each problem is reported once, in path order, and the rest of the crate is still scanned
+    let graph = TraversalEngine::new(root.clone()).try_scan().unwrap();
+    assert_eq!(graph.crates.len(), 1);
+    assert_eq!(find_snapshot(graph.crates[0].root_module(), "src/lib.rs").unwrap().functions.len(), 1);
+    let errors: Vec<String> = graph.errors.iter().map(|err| err.to_string()).collect();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(matches!(&graph.errors[0], ScanError::Manifest { path, .. } if path.ends_with("bad/Cargo.toml")));
+    assert!(matches!(&graph.errors[1], ScanError::Parse { path, line: 3, column: 16, .. } if path.ends_with("src/broken.rs")));
+    assert!(matches!(&graph.errors[2], ScanError::Io { path, .. } if path.ends_with("src/unreadable.rs")));
+    assert!(errors[1].ends_with("src/broken.rs:3:16: expected `:`"), "{}", errors[1]);
+
+    // Only a broken root manifest fails the scan as a whole
+    fs::write(root.join("Cargo.toml"), "not toml [").unwrap();
+    let err = TraversalEngine::new(root.clone()).try_scan().unwrap_err();
+    assert!(matches!(err, ScanError::Manifest { .. }));
+    assert_eq!(TraversalEngine::new(root.clone()).scan().errors, vec![err]);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_cargo_metadata_layout() {
+    let fixture = PathBuf::from("tests/fixtures/targets");
+    let targets = |graph: &furnace::graph::ProjectGraph| -> Vec<(String, TargetKind, PathBuf)> {
+        graph.crates[0].targets.iter()
+            .map(|t| (t.name.clone(), t.kind, t.root_module.file.as_ref().unwrap().path.clone()))
+            .collect()
+    };
+    let scan_with = |layout: LayoutSource| TraversalEngine::with_options(fixture.clone(), ScanOptions { layout, ..ScanOptions::default() }).scan();
+
+    // Cargo's view of the targets matches the manifest parser's, paths included
+    let mut from_manifest = targets(&scan_with(LayoutSource::Manifest));
+    let mut from_cargo = targets(&scan_with(LayoutSource::CargoMetadata));
+    from_manifest.sort();
+    from_cargo.sort();
+    assert_eq!(from_cargo, from_manifest);
+
+    // Saved output is taken as is: here, with every target but the library dropped
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = std::process::Command::new(cargo)
+        .args(["metadata", "--offline", "--no-deps", "--format-version", "1"])
+        .current_dir(&fixture)
+        .output()
+        .unwrap();
+    let mut metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    metadata["packages"][0]["targets"].as_array_mut().unwrap().retain(|t| t["kind"][0] == "lib");
+    let file = std::env::temp_dir().join(format!("furnace-metadata-{}.json", std::process::id()));
+    fs::write(&file, metadata.to_string()).unwrap();
+    let graph = scan_with(LayoutSource::MetadataFile(file.clone()));
+    assert_eq!(targets(&graph), vec![("targets_demo".to_string(), TargetKind::Lib, fixture.join("src/core.rs"))]);
+    fs::remove_file(&file).unwrap();
+
+    // An unreadable file is reported, and the manifests are parsed instead
+    let graph = scan_with(LayoutSource::MetadataFile(file));
+    assert!(matches!(graph.errors[..], [ScanError::Io { .. }]));
+    assert_eq!(graph.crates[0].targets.len(), from_manifest.len());
+}
+
+#[test]
+fn test_crate_dependency_graph() {
+    use furnace::graph::DependencyKind;
+
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/workspace")).scan();
+    let names: Vec<&str> = graph.crates.iter().map(|c| c.name.as_str()).collect();
+    let edges: Vec<(&str, &str, DependencyKind)> = graph.crate_edges().into_iter()
+        .map(|(from, to, kind)| (names[from], names[to], kind))
+        .collect();
+    assert_eq!(edges, vec![
+        ("ws-root", "ws-core", DependencyKind::Normal), // through `workspace = true`
+        ("ws-cli", "ws-core", DependencyKind::Normal),
+        ("ws-cli", "ws-util", DependencyKind::Dev),
+    ]);
+
+    let cli = graph.crates.iter().find(|c| c.name == "ws-cli").unwrap();
+    let deps: Vec<(&str, &str, DependencyKind, Option<&str>, bool)> = cli.dependencies.iter()
+        .map(|d| (d.name.as_str(), d.package.as_str(), d.kind, d.requirement.as_deref(), d.optional))
+        .collect();
+    assert_eq!(deps, vec![
+        ("core", "ws-core", DependencyKind::Normal, None, false),
+        ("serde", "serde", DependencyKind::Normal, Some("1.0"), false),
+        ("ws-experimental", "ws-experimental", DependencyKind::Normal, None, true),
+        ("ws-util", "ws-util", DependencyKind::Dev, Some("0.1"), false),
+        ("cc", "cc", DependencyKind::Build, Some("1"), false),
+    ]);
+
+    // Excluded from the workspace, so a path dependency but not an edge
+    let experimental = &cli.dependencies[2];
+    assert!(experimental.path.as_ref().unwrap().ends_with("crates/experimental"));
+    assert_eq!(experimental.workspace_crate, None);
+}
+
+#[test]
+fn test_crate_dependencies_from_cargo_metadata() {
+    use furnace::graph::DependencyKind;
+
+    let root = std::env::temp_dir().join(format!("furnace-metadata-deps-{}", std::process::id()));
+    for member in ["app", "base"] {
+        fs::create_dir_all(root.join(member).join("src")).unwrap();
+        fs::write(root.join(member).join("src/lib.rs"), "").unwrap();
+    }
+    fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"app\", \"base\"]\nresolver = \"2\"\n").unwrap();
+    fs::write(root.join("base/Cargo.toml"), "[package]\nname = \"base\"\nversion = \"0.1.0\"\n").unwrap();
+    fs::write(root.join("app/Cargo.toml"), "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nbase = { path = \"../base\" }\n\n[dev-dependencies]\nserde = \"1.0\"\n").unwrap();
+
+    let options = ScanOptions { layout: LayoutSource::CargoMetadata, ..ScanOptions::default() };
+    let graph = TraversalEngine::with_options(root.clone(), options).scan();
+    let names: Vec<&str> = graph.crates.iter().map(|c| c.name.as_str()).collect();
+    let app = names.iter().position(|name| *name == "app").unwrap();
+    let base = names.iter().position(|name| *name == "base").unwrap();
+    assert_eq!(graph.crate_edges(), vec![(app, base, DependencyKind::Normal)]);
+
+    let serde = graph.crates[app].dependencies.iter().find(|d| d.name == "serde").unwrap();
+    assert_eq!((serde.kind, serde.requirement.as_deref(), serde.workspace_crate), (DependencyKind::Dev, Some("^1.0"), None));
+    assert_eq!(graph.crates[app].dependencies.iter().find(|d| d.name == "base").unwrap().requirement, None);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_symbol_table_resolves_uses() {
+    use furnace::symbols::{Resolution, SymbolKind};
+
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/symbols")).scan();
+    let symbols = graph.crates[0].symbols();
+    let kind_of = |path: &str| symbols.get(path).map(|symbol| symbol.kind);
+    let found = |path: &str| symbols.find(path).map(|symbol| symbol.path.as_str());
+
+    assert_eq!(kind_of("crate::engine::Engine"), Some(SymbolKind::Struct));
+    assert_eq!(kind_of("crate::engine::Engine::new"), Some(SymbolKind::Method));
+    assert_eq!(kind_of("crate::engine::Engine::default"), Some(SymbolKind::Method)); // trait impl
+    assert_eq!(kind_of("crate::graph::Kind::Bin"), Some(SymbolKind::Variant));
+    assert_eq!(kind_of("crate::engine::tests::check"), Some(SymbolKind::Function)); // inline module
+    assert_eq!(kind_of("crate::node"), Some(SymbolKind::Macro)); // `#[macro_export]`
+
+    // Re-exports, by name and by glob, including a glob over enum variants
+    assert_eq!(found("crate::Engine"), Some("crate::engine::Engine"));
+    assert_eq!(found("crate::Node"), Some("crate::graph::Node"));
+    assert_eq!(found("crate::prelude::Runner"), Some("crate::engine::Engine"));
+    assert_eq!(found("crate::prelude::engine"), Some("crate::engine"));
+    assert_eq!(found("crate::prelude::Lib"), Some("crate::graph::Kind::Lib"));
+    // Globs only bring in what is visible from the importing module
+    assert_eq!(found("crate::private"), None);
+
+    // `use super::*` sees the parent's private items and imports
+    assert_eq!(symbols.resolve("crate::engine::tests", "Engine"), Some(Resolution::Local("crate::engine::Engine".to_string())));
+    assert_eq!(symbols.resolve("crate::engine::tests", "HashMap"), Some(Resolution::External("std::collections::HashMap".to_string())));
+    let missing = symbols.imports().iter().find(|import| import.path == "super::missing::Thing").unwrap();
+    assert_eq!((missing.module.as_str(), &missing.resolution), ("crate::engine", &None));
+
+    let (crate_idx, symbol) = graph.find_symbol(0, "crate::prelude::Runner").unwrap();
+    assert_eq!((crate_idx, symbol.path.as_str()), (0, "crate::engine::Engine"));
+
+    // Into another crate of the workspace, through a renamed path dependency
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/workspace")).scan();
+    let cli = graph.crates.iter().position(|c| c.name == "ws-cli").unwrap();
+    let (crate_idx, symbol) = graph.find_symbol(cli, "crate::run").unwrap();
+    assert_eq!((graph.crates[crate_idx].name.as_str(), symbol.path.as_str(), symbol.kind), ("ws-core", "crate::core", SymbolKind::Function));
+}
+
+#[test]
+fn test_call_graph() {
+    use furnace::calls::Callee;
+
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/calls")).scan();
+    let calls = &graph.calls;
+    let node = |path: &str| calls.node(0, path).unwrap();
+    let callees = |path: &str| -> Vec<String> {
+        calls.callees(node(path)).map(|edge| match &edge.callee {
+            Callee::Function(idx) => calls.nodes[*idx].path.clone(),
+            Callee::External(name) => format!("extern {}", name),
+        }).collect()
+    };
+
+    // Method calls resolve through the argument's type, `self` and `self.field`
+    assert_eq!(callees("crate::run"), vec!["crate::engine::Engine::step", "crate::helper", "crate::graph::build"]);
+    assert_eq!(callees("crate::engine::Engine::step"), vec!["crate::graph::Graph::size", "crate::engine::Engine::reset"]);
+    // Constructors are not calls; receivers of unknown type are external
+    assert_eq!(callees("crate::engine::Engine::reset"), vec![
+        "crate::engine::Engine::new",
+        "extern unwrap_or_default",
+        "extern map",
+        "extern size",
+    ]);
+    // Local closures are skipped; macro arguments are searched
+    assert_eq!(callees("crate::helper"), vec!["crate::graph::describe", "extern std::process::exit"]);
+
+    let build = node("crate::graph::build");
+    let callers: Vec<&str> = calls.callers(build).map(|edge| calls.nodes[edge.caller].path.as_str()).collect();
+    assert_eq!(callers, vec!["crate::engine::Engine::new", "crate::run"]);
+    let affected: Vec<&str> = calls.transitive_callers(build).into_iter().map(|idx| calls.nodes[idx].path.as_str()).collect();
+    assert_eq!(affected, vec!["crate::engine::Engine::new", "crate::run", "crate::engine::Engine::reset", "crate::engine::Engine::step"]);
+    let edge = calls.callers(build).next().unwrap();
+    assert_eq!((edge.location.path.as_str(), edge.location.span.start_line), ("tests/fixtures/calls/src/engine.rs", 9));
+
+    // Into another crate of the workspace
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/workspace")).scan();
+    let cli = graph.crates.iter().position(|c| c.name == "ws-cli").unwrap();
+    let edge = graph.calls.callees(graph.calls.node(cli, "crate::cli").unwrap()).next().unwrap();
+    let Callee::Function(target) = edge.callee else { panic!("unresolved: {:?}", edge.callee) };
+    assert_eq!(graph.crates[graph.calls.nodes[target].crate_idx].name, "ws-core");
+}
+
+#[test]
+fn test_type_dependency_graph() {
+    use furnace::type_graph::TypeUse;
+
+    let graph = TraversalEngine::new(PathBuf::from("tests/fixtures/type_graph")).scan();
+    let types = &graph.types;
+    let node = |path: &str| types.node(0, path).unwrap();
+    let dependencies = |path: &str| -> Vec<(TypeUse, String, &str)> {
+        types.dependencies(node(path)).map(|edge| (edge.kind, edge.via.clone(), types.nodes[edge.to].path.as_str())).collect()
+    };
+    let edge = |kind, via: &str, to| (kind, via.to_string(), to);
+
+    // Only types of the crate count: `Vec`, `Box` and `From` are not nodes; `-> Self` is no edge
+    assert_eq!(dependencies("crate::Engine"), vec![
+        edge(TypeUse::Field, "config", "crate::model::Config"),
+        edge(TypeUse::Field, "nodes", "crate::model::Node"),
+        edge(TypeUse::Field, "parent", "crate::Engine"),
+        edge(TypeUse::Argument, "new", "crate::model::Config"),
+        edge(TypeUse::Return, "events", "crate::Event"),
+        edge(TypeUse::Impl, "From", "crate::model::Config"),
+        edge(TypeUse::Argument, "from", "crate::model::Config"),
+    ]);
+    assert_eq!(dependencies("crate::Event"), vec![
+        edge(TypeUse::Variant, "Added", "crate::model::Node"),
+        edge(TypeUse::Variant, "Reset", "crate::model::Config"),
+    ]);
+    assert_eq!(dependencies("crate::NodeMap"), vec![edge(TypeUse::Alias, "NodeMap", "crate::model::Node")]);
+    assert_eq!(dependencies("crate::Visitor"), vec![edge(TypeUse::Argument, "visit", "crate::model::Node")]);
+    assert_eq!(dependencies("crate::model::Node"), vec![edge(TypeUse::Impl, "Visitor", "crate::Visitor")]);
+
+    let dependents: Vec<&str> = types.dependents(node("crate::model::Config")).map(|edge| types.nodes[edge.from].path.as_str()).collect();
+    assert_eq!(dependents, vec!["crate::Engine", "crate::Engine", "crate::Engine", "crate::Engine", "crate::Event"]);
+
+    let dot = types.to_dot(&graph);
+    assert!(dot.starts_with("digraph types {"));
+    assert!(dot.contains("label=\"type_graph (lib)\""));
+    let (engine, config) = (node("crate::Engine"), node("crate::model::Config"));
+    assert!(dot.contains(&format!("n{} -> n{} [label=\"field config\\nargument new\\nimpl From\\nargument from\"];", engine, config)));
+}
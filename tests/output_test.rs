@@ -1,40 +1,93 @@
-use furnace::output::{OutputStyle, OutputRenderer, Layout, Detail, ColorMode, SymbolSet};
-use furnace::types::{RustFileSnapshot, FunctionSnapshot, StructSnapshot, EnumSnapshot};
+#![allow(clippy::field_reassign_with_default)]
+
+use furnace::output::{OutputStyle, OutputRenderer, Layout, Detail, ColorMode};
+use furnace::types::{
+    RustFileSnapshot, FunctionSnapshot, FunctionSignature, ArgSnapshot, StructSnapshot, EnumSnapshot,
+    VariableSnapshot, Span, ConstSnapshot, MacroSnapshot, FieldSnapshot, VariantSnapshot, StructKind, Visibility,
+};
+
+fn line(n: usize) -> Span {
+    Span { start_line: n, start_column: 1, end_line: n, end_column: 1 }
+}
 
 fn create_sample_snapshot() -> RustFileSnapshot {
     RustFileSnapshot {
         path: "./src/example.rs".to_string(),
+        docs: Some("Example module.".to_string()),
         functions: vec![
             FunctionSnapshot {
                 name: "calculate".to_string(),
                 args: vec!["x".to_string(), "y".to_string()],
+                signature: FunctionSignature {
+                    inputs: vec![
+                        ArgSnapshot { pattern: "x".to_string(), ty: "i32".to_string(), ..ArgSnapshot::default() },
+                        ArgSnapshot { pattern: "y".to_string(), ty: "i32".to_string(), ..ArgSnapshot::default() },
+                    ],
+                    output: Some("i32".to_string()),
+                    ..FunctionSignature::default()
+                },
+                visibility: Visibility::Public,
+                docs: Some("Combines two numbers.\n\nUsed by the scheduler.".to_string()),
                 variables: vec![
-                    ("result".to_string(), Some("i32".to_string())),
-                    ("temp".to_string(), None),
+                    VariableSnapshot { name: "result".to_string(), ty: Some("i32".to_string()), type_path: Some("i32".to_string()), mutable: false, scope_depth: 0, span: line(4) },
+                    VariableSnapshot { name: "temp".to_string(), ty: None, type_path: None, mutable: true, scope_depth: 1, span: line(5) },
                 ],
+                span: line(3),
+                ..FunctionSnapshot::default()
             },
             FunctionSnapshot {
                 name: "process_data".to_string(),
                 args: vec!["data".to_string()],
+                signature: FunctionSignature {
+                    inputs: vec![ArgSnapshot { pattern: "data".to_string(), ty: "&[u8]".to_string(), ..ArgSnapshot::default() }],
+                    ..FunctionSignature::default()
+                },
                 variables: vec![],
+                span: line(9),
+                ..FunctionSnapshot::default()
             },
         ],
         structs: vec![
             StructSnapshot {
                 name: "Config".to_string(),
-                fields: vec!["host".to_string(), "port".to_string()],
+                kind: StructKind::Named,
+                fields: vec![
+                    FieldSnapshot { name: "host".to_string(), ty: "String".to_string(), span: line(13), ..FieldSnapshot::default() },
+                    FieldSnapshot { name: "port".to_string(), ty: "u16".to_string(), span: line(14), ..FieldSnapshot::default() },
+                ],
                 methods: vec!["new".to_string(), "validate".to_string()],
+                derives: vec!["Debug".to_string(), "Clone".to_string()],
+                span: line(12),
+                ..StructSnapshot::default()
             },
         ],
         enums: vec![
             EnumSnapshot {
                 name: "Status".to_string(),
-                variants: vec!["Active".to_string(), "Inactive".to_string()],
+                variants: vec![
+                    VariantSnapshot { name: "Active".to_string(), span: line(18), ..VariantSnapshot::default() },
+                    VariantSnapshot {
+                        name: "Failed".to_string(),
+                        kind: StructKind::Tuple,
+                        fields: vec![FieldSnapshot { name: "0".to_string(), ty: "String".to_string(), span: line(19), ..FieldSnapshot::default() }],
+                        span: line(19),
+                        ..VariantSnapshot::default()
+                    },
+                ],
                 methods: vec!["is_active".to_string()],
+                span: line(17),
+                ..EnumSnapshot::default()
             },
         ],
         traits: vec![],
         impls: vec![],
+        consts: vec![
+            ConstSnapshot { name: "MAX_RETRIES".to_string(), ty: "u32".to_string(), span: line(21), ..ConstSnapshot::default() },
+        ],
+        macros: vec![
+            MacroSnapshot { name: "retry".to_string(), span: line(23), ..MacroSnapshot::default() },
+        ],
+        ..RustFileSnapshot::default()
     }
 }
 
@@ -50,6 +103,9 @@ fn test_plain_output() {
     assert!(output.contains("calculate"));
     assert!(output.contains("Structs"));
     assert!(output.contains("Config"));
+    assert!(output.contains("Constants"));
+    assert!(output.contains("MAX_RETRIES: u32 @ ./src/example.rs:21"));
+    assert!(output.contains("retry!"));
 }
 
 #[test]
@@ -113,6 +169,43 @@ fn test_verbose_detail() {
     // Verbose should show args and variables counts
     assert!(output.contains("calculate"));
     assert!(output.contains("args:") || output.contains("vars:"));
+    assert!(output.contains("fn calculate(x: i32, y: i32) -> i32"));
+}
+
+#[test]
+fn test_field_types_and_variant_payloads() {
+    let style = OutputStyle { detail: Detail::Verbose, ..OutputStyle::tree() };
+    let output = OutputRenderer::new(style).render(&[create_sample_snapshot()]);
+
+    assert!(output.contains("fields [host: String, port: u16]"));
+    assert!(output.contains("variants [Active, Failed(String)]"));
+}
+
+#[test]
+fn test_verbose_visibility_and_derives() {
+    for layout in [Layout::Plain, Layout::Tree] {
+        let style = OutputStyle { layout, detail: Detail::Verbose, color: ColorMode::None, ..OutputStyle::default() };
+        let output = OutputRenderer::new(style).render(&[create_sample_snapshot()]);
+
+        assert!(output.contains("pub fn calculate("), "{:?} output lacks visibility", layout);
+        assert!(!output.contains("pub fn process_data("), "{:?} output marks a private fn as pub", layout);
+        assert!(output.contains("derives [Debug, Clone]"), "{:?} output lacks derives", layout);
+    }
+}
+
+#[test]
+fn test_verbose_doc_summaries() {
+    for layout in [Layout::Plain, Layout::Tree] {
+        let style = OutputStyle { layout, detail: Detail::Verbose, color: ColorMode::None, ..OutputStyle::default() };
+        let output = OutputRenderer::new(style).render(&[create_sample_snapshot()]);
+
+        assert!(output.contains("/// Example module."), "{:?} output lacks file docs", layout);
+        assert!(output.contains("/// Combines two numbers."), "{:?} output lacks function docs", layout);
+        assert!(!output.contains("scheduler"), "{:?} output shows more than the summary", layout);
+    }
+
+    let output = OutputRenderer::new(OutputStyle::default()).render(&[create_sample_snapshot()]);
+    assert!(!output.contains("///"));
 }
 
 #[test]
@@ -138,6 +231,7 @@ fn test_empty_snapshot() {
         enums: vec![],
         traits: vec![],
         impls: vec![],
+        ..RustFileSnapshot::default()
     };
     
     let style = OutputStyle::default();
@@ -170,3 +264,16 @@ fn test_badges_color_mode() {
     
     assert!(output.contains("📁") || output.contains("🔧") || output.contains("🏗️"));
 }
+
+#[test]
+fn test_every_layout_shows_locations() {
+    let layouts = [Layout::Plain, Layout::Tree, Layout::Grid, Layout::Compact];
+    for layout in layouts {
+        let style = OutputStyle { layout, ..OutputStyle::default() };
+        let output = OutputRenderer::new(style).render(&[create_sample_snapshot()]);
+
+        assert!(output.contains("./src/example.rs:3"), "{:?} output lacks function location", layout);
+        assert!(output.contains("./src/example.rs:12"), "{:?} output lacks struct location", layout);
+        assert!(output.contains("./src/example.rs:17"), "{:?} output lacks enum location", layout);
+    }
+}